@@ -0,0 +1,102 @@
+//! Fixed point against `f32` + `libm` for the operations the games lean on.
+//!
+//! `cargo bench --target x86_64-unknown-linux-gnu` (or whatever the host is). These are host
+//! timings and only say how the two compare on the host, they are not M0+ cycles. The host has
+//! an FPU so the plain `f32` arithmetic rows flatter floats, on the RP2040 every one of those is
+//! a soft-float call as well. The `libm` rows are software on both, so they are the better
+//! guide. Measure on the device before switching a game over for speed.
+//!
+//! Inputs are kept to what `Fx` can hold, about ±32768, so both sides do real work.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use frugger_core::fixed::{Fx, FxVec2};
+
+const ITERS: u32 = 1_000_000;
+/// Keeps `i` in range for `Fx::from_int`.
+const INT_MASK: u32 = 0x7FFF;
+
+fn bench<T>(name: &str, mut f: impl FnMut(u32) -> T) -> f64 {
+    // Warm up
+    for i in 0..ITERS / 10 {
+        black_box(f(black_box(i)));
+    }
+
+    let start = Instant::now();
+    for i in 0..ITERS {
+        black_box(f(black_box(i)));
+    }
+    let ns = start.elapsed().as_nanos() as f64 / ITERS as f64;
+    println!("{name:<24} {ns:>8.2} ns/iter");
+    ns
+}
+
+fn compare<A, B>(name: &str, float: impl FnMut(u32) -> A, fixed: impl FnMut(u32) -> B) {
+    let f = bench(&format!("{name} f32"), float);
+    let x = bench(&format!("{name} fx"), fixed);
+    println!("{:<24} {:>8.2}x\n", format!("{name} speedup"), f / x);
+}
+
+fn main() {
+    println!("host timings, relative only, not M0+ cycles\n");
+    compare(
+        "mul",
+        |i| (i & INT_MASK) as f32 * 0.035 * 1.2,
+        |i| Fx::from_int((i & INT_MASK) as i32) * Fx::from_f32(0.035) * Fx::from_f32(1.2),
+    );
+
+    compare(
+        "div",
+        |i| (i & INT_MASK) as f32 / 3.7,
+        |i| Fx::from_int((i & INT_MASK) as i32) / Fx::from_f32(3.7),
+    );
+
+    compare(
+        "sin",
+        |i| libm::sinf(i as f32 * 0.001),
+        |i| Fx::from_raw((i as i32) * 66).sin(),
+    );
+
+    compare(
+        "cos",
+        |i| libm::cosf(i as f32 * 0.001),
+        |i| Fx::from_raw((i as i32) * 66).cos(),
+    );
+
+    // `from_raw(i)` is i / 65536, the same value on both sides as the cost depends on it
+    compare(
+        "sqrt",
+        |i| libm::sqrt(i as f64 / 65536.0) as f32,
+        |i| Fx::from_raw(i as i32).sqrt(),
+    );
+
+    compare(
+        "round",
+        |i| libm::roundf((i & INT_MASK) as f32 * 0.37) as i32,
+        |i| Fx::from_raw((i & INT_MASK) as i32 * 24248).round(),
+    );
+
+    // One step of the worm's head, which is most of its per frame maths
+    compare(
+        "worm step",
+        |i| {
+            let dir = i as f32 * 0.01;
+            let speed = 0.5;
+            let x = (32.0 + speed * libm::cosf(dir) + 64.0) % 64.0;
+            let y = (64.0 + speed * libm::sinf(dir) + 128.0) % 128.0;
+            (libm::roundf(x) as i32, libm::roundf(y) as i32)
+        },
+        |i| {
+            let dir = Fx::from_raw(i as i32 * 655);
+            let speed = Fx::HALF;
+            let head =
+                FxVec2::new(Fx::from_int(32), Fx::from_int(64)) + FxVec2::from_angle(dir) * speed;
+            let head = FxVec2::new(
+                head.x.rem_euclid(Fx::from_int(64)),
+                head.y.rem_euclid(Fx::from_int(128)),
+            );
+            head.round()
+        },
+    );
+}
@@ -0,0 +1,616 @@
+//! 2D collision tests shared by the games. Everything works on squared distances where it can
+//! so there is no sqrt on the hot paths.
+
+use core::ops::{Add, Mul, Neg, Sub};
+use embedded_graphics::geometry::Point;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vec2 {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn dot(self, other: Vec2) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Z of the 3D cross product, positive if `other` is clockwise from `self` in screen
+    /// coordinates (y down).
+    pub fn cross(self, other: Vec2) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn length_sq(self) -> f32 {
+        self.dot(self)
+    }
+
+    pub fn distance_sq(self, other: Vec2) -> f32 {
+        (other - self).length_sq()
+    }
+}
+
+impl From<Point> for Vec2 {
+    fn from(p: Point) -> Self {
+        Vec2::new(p.x as f32, p.y as f32)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, rhs: f32) -> Vec2 {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+/// Axis aligned box, `min` is inclusive and `max` exclusive like pixels: a box at 0 that is 10
+/// wide ends just before 10. Boxes that only touch don't overlap.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub const fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_size(top_left: Vec2, width: f32, height: f32) -> Self {
+        Self::new(top_left, top_left + Vec2::new(width, height))
+    }
+
+    /// Smallest box holding everything from `min` to `max`, `max` included.
+    pub fn covering(min: Vec2, max: Vec2) -> Self {
+        Self::new(min, Vec2::new(max.x.next_up(), max.y.next_up()))
+    }
+
+    pub fn contains(&self, p: Vec2) -> bool {
+        p.x >= self.min.x && p.x < self.max.x && p.y >= self.min.y && p.y < self.max.y
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x < other.max.x
+            && other.min.x < self.max.x
+            && self.min.y < other.max.y
+            && other.min.y < self.max.y
+    }
+
+    /// Closest point in the box, so past the max edges it is the last value before them.
+    pub fn closest_point(&self, p: Vec2) -> Vec2 {
+        let last = |min: f32, max: f32| max.next_down().max(min);
+        Vec2::new(
+            p.x.clamp(self.min.x, last(self.min.x, self.max.x)),
+            p.y.clamp(self.min.y, last(self.min.y, self.max.y)),
+        )
+    }
+
+    /// Smallest box holding both.
+    pub fn merge(&self, other: &Aabb) -> Aabb {
+        Aabb::new(
+            Vec2::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            Vec2::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        )
+    }
+
+    /// Box covering everything this one touches while moving by `vel`.
+    pub fn swept(&self, vel: Vec2) -> Aabb {
+        self.merge(&Aabb::new(self.min + vel, self.max + vel))
+    }
+
+    /// Grow the box by `by` on every side.
+    pub fn expand(&self, by: f32) -> Aabb {
+        Aabb::new(self.min - Vec2::new(by, by), self.max + Vec2::new(by, by))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub const fn new(center: Vec2, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Points on the edge count as inside.
+    pub fn contains(&self, p: Vec2) -> bool {
+        self.center.distance_sq(p) <= self.radius * self.radius
+    }
+
+    pub fn intersects(&self, other: &Circle) -> bool {
+        let r = self.radius + other.radius;
+        self.center.distance_sq(other.center) <= r * r
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.contains(aabb.closest_point(self.center))
+    }
+
+    pub fn intersects_segment(&self, segment: &Segment) -> bool {
+        self.contains(segment.closest_point(self.center))
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        Aabb::covering(
+            self.center - Vec2::new(self.radius, self.radius),
+            self.center + Vec2::new(self.radius, self.radius),
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Segment {
+    pub a: Vec2,
+    pub b: Vec2,
+}
+
+impl Segment {
+    pub const fn new(a: Vec2, b: Vec2) -> Self {
+        Self { a, b }
+    }
+
+    /// Which side of the (infinite) line through the segment `p` is on. Positive is clockwise
+    /// from `a -> b` in screen coordinates, 0 is on the line.
+    pub fn side(&self, p: Vec2) -> f32 {
+        (self.b - self.a).cross(p - self.a)
+    }
+
+    pub fn closest_point(&self, p: Vec2) -> Vec2 {
+        let ab = self.b - self.a;
+        let len_sq = ab.length_sq();
+        if len_sq == 0.0 {
+            return self.a;
+        }
+        let t = ((p - self.a).dot(ab) / len_sq).clamp(0.0, 1.0);
+        self.a + ab * t
+    }
+
+    /// Fraction along `self` where it crosses `other`, if it does.
+    pub fn intersection(&self, other: &Segment) -> Option<f32> {
+        let r = self.b - self.a;
+        let s = other.b - other.a;
+        let denom = r.cross(s);
+        if denom == 0.0 {
+            // Parallel, we don't count overlapping collinear segments
+            return None;
+        }
+        let qp = other.a - self.a;
+        let t = qp.cross(s) / denom;
+        let u = qp.cross(r) / denom;
+        ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+    }
+
+    pub fn intersects(&self, other: &Segment) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Fraction along the segment where it enters the box, 0 if it starts inside.
+    pub fn intersection_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        self.entry(aabb, false)
+    }
+
+    /// Clips the segment to the box one axis at a time. With `min_open` the min edges are
+    /// outside too, otherwise only the max ones.
+    fn entry(&self, aabb: &Aabb, min_open: bool) -> Option<f32> {
+        let dir = self.b - self.a;
+        // Range of fractions inside so far, and whether each end is just outside
+        let (mut t_min, mut t_min_open) = (0.0f32, false);
+        let (mut t_max, mut t_max_open) = (1.0f32, false);
+
+        for (origin, d, min, max) in [
+            (self.a.x, dir.x, aabb.min.x, aabb.max.x),
+            (self.a.y, dir.y, aabb.min.y, aabb.max.y),
+        ] {
+            if d == 0.0 {
+                if origin < min || (min_open && origin == min) || origin >= max {
+                    return None;
+                }
+                continue;
+            }
+
+            let at_min = ((min - origin) / d, min_open);
+            let at_max = ((max - origin) / d, true);
+            let (enter, exit) = if d > 0.0 {
+                (at_min, at_max)
+            } else {
+                (at_max, at_min)
+            };
+            if enter.0 > t_min {
+                (t_min, t_min_open) = enter;
+            } else if enter.0 == t_min {
+                t_min_open |= enter.1;
+            }
+            if exit.0 < t_max {
+                (t_max, t_max_open) = exit;
+            } else if exit.0 == t_max {
+                t_max_open |= exit.1;
+            }
+
+            if t_min > t_max || (t_min == t_max && (t_min_open || t_max_open)) {
+                return None;
+            }
+        }
+
+        Some(t_min)
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        Aabb::covering(
+            Vec2::new(self.a.x.min(self.b.x), self.a.y.min(self.b.y)),
+            Vec2::new(self.a.x.max(self.b.x), self.a.y.max(self.b.y)),
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Triangle {
+    pub a: Vec2,
+    pub b: Vec2,
+    pub c: Vec2,
+}
+
+impl Triangle {
+    pub const fn new(a: Vec2, b: Vec2, c: Vec2) -> Self {
+        Self { a, b, c }
+    }
+
+    /// Works for either winding, points on an edge count as inside.
+    pub fn contains(&self, p: Vec2) -> bool {
+        let d1 = (self.b - self.a).cross(p - self.a);
+        let d2 = (self.c - self.b).cross(p - self.b);
+        let d3 = (self.a - self.c).cross(p - self.c);
+        let neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+        !(neg && pos)
+    }
+
+    pub fn edges(&self) -> [Segment; 3] {
+        [
+            Segment::new(self.a, self.b),
+            Segment::new(self.b, self.c),
+            Segment::new(self.c, self.a),
+        ]
+    }
+
+    pub fn intersects_circle(&self, circle: &Circle) -> bool {
+        self.contains(circle.center) || self.edges().iter().any(|e| circle.intersects_segment(e))
+    }
+
+    /// Separating axis test against the box axes and the triangle's edge normals.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        if !self.bounds().intersects(aabb) {
+            return false;
+        }
+
+        let corners = [
+            aabb.min,
+            Vec2::new(aabb.max.x, aabb.min.y),
+            aabb.max,
+            Vec2::new(aabb.min.x, aabb.max.y),
+        ];
+        let points = [self.a, self.b, self.c];
+
+        self.edges().iter().all(|edge| {
+            let dir = edge.b - edge.a;
+            let normal = Vec2::new(-dir.y, dir.x);
+            let (t_min, t_max) = project(&points, normal);
+            let (b_min, b_max) = project(&corners, normal);
+            t_min <= b_max && b_min <= t_max
+        })
+    }
+
+    pub fn intersects_segment(&self, segment: &Segment) -> bool {
+        self.contains(segment.a) || self.edges().iter().any(|e| e.intersects(segment))
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        Aabb::covering(
+            Vec2::new(
+                self.a.x.min(self.b.x).min(self.c.x),
+                self.a.y.min(self.b.y).min(self.c.y),
+            ),
+            Vec2::new(
+                self.a.x.max(self.b.x).max(self.c.x),
+                self.a.y.max(self.b.y).max(self.c.y),
+            ),
+        )
+    }
+}
+
+fn project(points: &[Vec2], axis: Vec2) -> (f32, f32) {
+    points.iter().fold((f32::MAX, f32::MIN), |(min, max), p| {
+        let d = p.dot(axis);
+        (min.min(d), max.max(d))
+    })
+}
+
+/// Time of impact in `0..=1` for `moving` travelling by `vel` into `target`, so fast movers
+/// can't tunnel through thin objects. 0 if they already overlap. Like `Aabb::intersects`,
+/// ending up touching or sliding along an edge isn't an impact.
+pub fn swept_aabb(moving: &Aabb, vel: Vec2, target: &Aabb) -> Option<f32> {
+    if moving.intersects(target) {
+        return Some(0.0);
+    }
+
+    // Shrink the moving box to a point and grow the target to match
+    let half = (moving.max - moving.min) * 0.5;
+    let grown = Aabb::new(target.min - half, target.max + half);
+    let center = moving.min + half;
+    // The boxes overlap while the center is strictly inside the grown box
+    Segment::new(center, center + vel).entry(&grown, true)
+}
+
+/// Time of impact in `0..=1` for `moving` travelling by `vel` into the static `target`.
+pub fn swept_circle(moving: &Circle, vel: Vec2, target: &Circle) -> Option<f32> {
+    let r = moving.radius + target.radius;
+    let offset = moving.center - target.center;
+    let c = offset.length_sq() - r * r;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+
+    // Solve |offset + vel * t| = r for t
+    let a = vel.length_sq();
+    let b = offset.dot(vel);
+    if a == 0.0 || b >= 0.0 {
+        // Not moving, or moving away
+        return None;
+    }
+    let disc = b * b - a * c;
+    if disc < 0.0 {
+        return None;
+    }
+    let t = (-b - libm::sqrtf(disc)) / a;
+    (t <= 1.0).then_some(t)
+}
+
+/// Sort and sweep broad phase. Sorts `boxes` by their left edge and calls `f` with the ids of
+/// every pair of overlapping boxes.
+pub fn overlapping_pairs<F>(boxes: &mut [(usize, Aabb)], mut f: F)
+where
+    F: FnMut(usize, usize),
+{
+    boxes.sort_unstable_by(|(_, a), (_, b)| a.min.x.total_cmp(&b.min.x));
+
+    for (i, (id, a)) in boxes.iter().enumerate() {
+        for (other, b) in &boxes[i + 1..] {
+            if b.min.x >= a.max.x {
+                break;
+            }
+            if a.intersects(b) {
+                f(*id, *other);
+            }
+        }
+    }
+}
+
+/// Ids of the boxes overlapping `query`.
+pub fn query<'a>(boxes: &'a [(usize, Aabb)], query: &'a Aabb) -> impl Iterator<Item = usize> + 'a {
+    boxes
+        .iter()
+        .filter(|(_, b)| b.intersects(query))
+        .map(|(id, _)| *id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f32, y: f32) -> Vec2 {
+        Vec2::new(x, y)
+    }
+
+    #[test]
+    fn aabb_overlap() {
+        let a = Aabb::from_size(v(0.0, 0.0), 10.0, 10.0);
+        assert!(a.intersects(&Aabb::from_size(v(5.0, 5.0), 10.0, 10.0)));
+        // Touching edges don't overlap
+        assert!(!a.intersects(&Aabb::from_size(v(10.0, 0.0), 10.0, 10.0)));
+        assert!(a.contains(v(0.0, 0.0)));
+        assert!(!a.contains(v(10.0, 5.0)));
+    }
+
+    #[test]
+    fn circles() {
+        let c = Circle::new(v(0.0, 0.0), 2.0);
+        assert!(c.contains(v(2.0, 0.0)));
+        assert!(!c.contains(v(2.0, 0.1)));
+        assert!(c.intersects(&Circle::new(v(3.0, 0.0), 1.0)));
+        assert!(!c.intersects(&Circle::new(v(3.1, 0.0), 1.0)));
+        assert!(c.intersects_aabb(&Aabb::from_size(v(1.0, 1.0), 5.0, 5.0)));
+        assert!(!c.intersects_aabb(&Aabb::from_size(v(2.0, 2.0), 5.0, 5.0)));
+    }
+
+    #[test]
+    fn segments() {
+        let s = Segment::new(v(0.0, 0.0), v(10.0, 10.0));
+        let t = s
+            .intersection(&Segment::new(v(0.0, 10.0), v(10.0, 0.0)))
+            .unwrap();
+        assert!((t - 0.5).abs() < 1e-6);
+        assert!(!s.intersects(&Segment::new(v(1.0, 0.0), v(11.0, 10.0))));
+
+        // Clockwise of a wall running up the screen is to its right
+        let wall = Segment::new(v(10.0, 128.0), v(10.0, -200.0));
+        assert!(wall.side(v(20.0, 100.0)) > 0.0);
+        assert!(wall.side(v(0.0, 100.0)) < 0.0);
+
+        assert_eq!(s.closest_point(v(10.0, 0.0)), v(5.0, 5.0));
+        assert_eq!(s.closest_point(v(-5.0, -5.0)), v(0.0, 0.0));
+    }
+
+    #[test]
+    fn segment_box() {
+        let b = Aabb::from_size(v(10.0, 0.0), 10.0, 10.0);
+        let t = Segment::new(v(0.0, 5.0), v(20.0, 5.0))
+            .intersection_aabb(&b)
+            .unwrap();
+        assert!((t - 0.5).abs() < 1e-6);
+        assert_eq!(
+            Segment::new(v(0.0, 20.0), v(20.0, 20.0)).intersection_aabb(&b),
+            None
+        );
+        assert_eq!(
+            Segment::new(v(15.0, 5.0), v(30.0, 5.0)).intersection_aabb(&b),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn triangles() {
+        let tri = Triangle::new(v(0.0, 0.0), v(-3.0, 10.0), v(3.0, 10.0));
+        assert!(tri.contains(v(0.0, 5.0)));
+        assert!(tri.contains(v(0.0, 0.0)));
+        assert!(!tri.contains(v(3.0, 1.0)));
+
+        assert!(tri.intersects_aabb(&Aabb::from_size(v(-1.0, 4.0), 2.0, 2.0)));
+        assert!(!tri.intersects_aabb(&Aabb::from_size(v(2.0, 0.0), 2.0, 2.0)));
+        assert!(tri.intersects_circle(&Circle::new(v(3.0, 5.0), 2.0)));
+        assert!(!tri.intersects_circle(&Circle::new(v(5.0, 0.0), 1.0)));
+        assert!(tri.intersects_segment(&Segment::new(v(-5.0, 5.0), v(5.0, 5.0))));
+    }
+
+    #[test]
+    fn swept() {
+        // Would tunnel straight through a 1px wall in one step
+        let bullet = Aabb::from_size(v(0.0, 0.0), 2.0, 2.0);
+        let wall = Aabb::from_size(v(10.0, -10.0), 1.0, 20.0);
+        let t = swept_aabb(&bullet, v(20.0, 0.0), &wall).unwrap();
+        assert!((t - 0.4).abs() < 1e-6);
+        assert_eq!(swept_aabb(&bullet, v(0.0, 20.0), &wall), None);
+
+        let ball = Circle::new(v(0.0, 0.0), 1.0);
+        let t = swept_circle(&ball, v(10.0, 0.0), &Circle::new(v(6.0, 0.0), 1.0)).unwrap();
+        assert!((t - 0.4).abs() < 1e-6);
+        assert_eq!(
+            swept_circle(&ball, v(-10.0, 0.0), &Circle::new(v(6.0, 0.0), 1.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn broad_phase() {
+        let mut boxes = [
+            (0, Aabb::from_size(v(0.0, 0.0), 5.0, 5.0)),
+            (1, Aabb::from_size(v(50.0, 0.0), 5.0, 5.0)),
+            (2, Aabb::from_size(v(3.0, 3.0), 5.0, 5.0)),
+            (3, Aabb::from_size(v(4.0, 30.0), 5.0, 5.0)),
+        ];
+        let mut pairs = [(0, 0); 4];
+        let mut n = 0;
+        overlapping_pairs(&mut boxes, |a, b| {
+            pairs[n] = (a.min(b), a.max(b));
+            n += 1;
+        });
+        assert_eq!(&pairs[..n], &[(0, 2)]);
+
+        let area = Aabb::from_size(v(45.0, 0.0), 10.0, 1.0);
+        assert!(query(&boxes, &area).eq([1]));
+    }
+
+    #[test]
+    fn aabb_edges() {
+        // Covers 0 up to but not including 10, like a row of 10 pixels
+        let a = Aabb::from_size(v(0.0, 0.0), 10.0, 10.0);
+        assert!(a.contains(v(9.99, 9.99)));
+        assert!(!a.contains(v(5.0, 10.0)));
+        assert!(!a.intersects(&Aabb::from_size(v(0.0, 10.0), 10.0, 10.0)));
+        assert!(!a.intersects(&Aabb::from_size(v(-10.0, 0.0), 10.0, 10.0)));
+        assert!(a.intersects(&Aabb::from_size(v(-9.99, 0.0), 10.0, 10.0)));
+
+        // Closest points are inside
+        assert!(a.contains(a.closest_point(v(20.0, 20.0))));
+        assert_eq!(a.closest_point(v(-5.0, -5.0)), v(0.0, 0.0));
+        let empty = Aabb::new(v(3.0, 3.0), v(3.0, 3.0));
+        assert_eq!(empty.closest_point(v(9.0, 0.0)), v(3.0, 3.0));
+
+        // A circle touching the min edge shares a point with the box, one touching max doesn't
+        assert!(Circle::new(v(-2.0, 5.0), 2.0).intersects_aabb(&a));
+        assert!(!Circle::new(v(12.0, 5.0), 2.0).intersects_aabb(&a));
+
+        // Bounds of closed shapes hold their max points
+        let s = Segment::new(v(10.0, 0.0), v(0.0, 5.0));
+        assert!(s.bounds().contains(s.a) && s.bounds().contains(s.b));
+        let tri = Triangle::new(v(0.0, 0.0), v(4.0, 0.0), v(0.0, 4.0));
+        assert!(tri.bounds().contains(v(4.0, 0.0)));
+        assert!(tri.intersects_aabb(&Aabb::from_size(v(4.0, -1.0), 2.0, 2.0)));
+        assert!(!tri.intersects_aabb(&Aabb::from_size(v(2.0, -2.0), 2.0, 2.0)));
+    }
+
+    #[test]
+    fn segment_box_edges() {
+        let b = Aabb::from_size(v(10.0, 0.0), 10.0, 10.0);
+        // Ending on the min edge gets in, on the max edge doesn't
+        assert_eq!(
+            Segment::new(v(0.0, 5.0), v(10.0, 5.0)).intersection_aabb(&b),
+            Some(1.0)
+        );
+        assert_eq!(
+            Segment::new(v(30.0, 5.0), v(20.0, 5.0)).intersection_aabb(&b),
+            None
+        );
+        // Along the top edge is inside, along the bottom isn't
+        assert_eq!(
+            Segment::new(v(0.0, 0.0), v(20.0, 0.0)).intersection_aabb(&b),
+            Some(0.5)
+        );
+        assert_eq!(
+            Segment::new(v(0.0, 10.0), v(20.0, 10.0)).intersection_aabb(&b),
+            None
+        );
+        // Corner to corner across the open one
+        assert_eq!(
+            Segment::new(v(25.0, 5.0), v(15.0, 15.0)).intersection_aabb(&b),
+            None
+        );
+    }
+
+    #[test]
+    fn swept_edges() {
+        let bullet = Aabb::from_size(v(0.0, 0.0), 2.0, 2.0);
+        let wall = Aabb::from_size(v(10.0, -10.0), 1.0, 20.0);
+        // Stops touching the wall
+        assert_eq!(swept_aabb(&bullet, v(8.0, 0.0), &wall), None);
+        let t = swept_aabb(&bullet, v(8.5, 0.0), &wall).unwrap();
+        assert!((t - 8.0 / 8.5).abs() < 1e-6);
+
+        // Sliding along either end of the wall
+        let above = Aabb::from_size(v(9.0, -12.0), 2.0, 2.0);
+        assert_eq!(swept_aabb(&above, v(2.0, 0.0), &wall), None);
+        let below = Aabb::from_size(v(9.0, 10.0), 2.0, 2.0);
+        assert_eq!(swept_aabb(&below, v(2.0, 0.0), &wall), None);
+        assert_eq!(swept_aabb(&below, v(2.0, -0.5), &wall), Some(0.0));
+    }
+}
@@ -0,0 +1,465 @@
+//! 16.16 fixed point numbers for the RP2040, which has no FPU so every `f32` op and `libm` call
+//! is done in software. `Fx` mirrors the `f32` operations the games use so positions, speeds
+//! and angles can be swapped over one at a time. `cargo bench` compares the two on the host.
+//!
+//! Overflow wraps in every operation, in debug and release builds alike, the same as `i32` in
+//! release. The range is about ±32768, far past anything on screen. Dividing by zero panics.
+
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+
+use crate::collision::Vec2;
+
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Fx(i32);
+
+/// sin over a quarter turn in 64 steps, interpolated between.
+const SIN_TABLE: [i32; 65] = [
+    0, 1608, 3216, 4821, 6424, 8022, 9616, 11204, 12785, 14359, 15924, 17479, 19024, 20557, 22078,
+    23586, 25080, 26558, 28020, 29466, 30893, 32303, 33692, 35062, 36410, 37736, 39040, 40320,
+    41576, 42806, 44011, 45190, 46341, 47464, 48559, 49624, 50660, 51665, 52639, 53581, 54491,
+    55368, 56212, 57022, 57798, 58538, 59244, 59914, 60547, 61145, 61705, 62228, 62714, 63162,
+    63572, 63944, 64277, 64571, 64827, 65043, 65220, 65358, 65457, 65516, 65536,
+];
+
+/// Table steps per radian (256 / tau) as 16.16.
+const STEPS_PER_RAD: i64 = 2670177;
+
+impl Fx {
+    pub const FRAC_BITS: u32 = 16;
+    pub const ZERO: Fx = Fx(0);
+    pub const ONE: Fx = Fx(1 << 16);
+    pub const HALF: Fx = Fx(1 << 15);
+    pub const PI: Fx = Fx(205887);
+    pub const FRAC_PI_2: Fx = Fx(102944);
+    pub const TAU: Fx = Fx(411775);
+    pub const MAX: Fx = Fx(i32::MAX);
+    pub const MIN: Fx = Fx(i32::MIN);
+
+    pub const fn from_raw(raw: i32) -> Self {
+        Fx(raw)
+    }
+
+    pub const fn raw(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(v: i32) -> Self {
+        Fx(v.wrapping_shl(16))
+    }
+
+    /// Rounds to the nearest step, for constants.
+    pub const fn from_f32(v: f32) -> Self {
+        let scaled = v * 65536.0;
+        Fx(if scaled < 0.0 {
+            (scaled - 0.5) as i32
+        } else {
+            (scaled + 0.5) as i32
+        })
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / 65536.0
+    }
+
+    pub const fn floor(self) -> i32 {
+        self.0 >> 16
+    }
+
+    /// Round half away from zero, same as `libm::roundf`.
+    pub const fn round(self) -> i32 {
+        if self.0 < 0 {
+            -((-(self.0 as i64) + (1 << 15)) >> 16) as i32
+        } else {
+            ((self.0 as i64 + (1 << 15)) >> 16) as i32
+        }
+    }
+
+    pub const fn abs(self) -> Self {
+        Fx(self.0.wrapping_abs())
+    }
+
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn min(self, other: Fx) -> Fx {
+        Ord::min(self, other)
+    }
+
+    pub fn max(self, other: Fx) -> Fx {
+        Ord::max(self, other)
+    }
+
+    pub fn clamp(self, min: Fx, max: Fx) -> Fx {
+        Ord::clamp(self, min, max)
+    }
+
+    pub const fn rem_euclid(self, rhs: Fx) -> Fx {
+        Fx(self.0.wrapping_rem_euclid(rhs.0))
+    }
+
+    /// Table lookup, within 1e-4 of `sinf`.
+    pub fn sin(self) -> Fx {
+        // Position around the circle in table steps, 256 per turn, wrapped for free by the mask
+        let steps = ((self.0 as i64 * STEPS_PER_RAD) >> 16) as i32 & ((256 << 16) - 1);
+        let idx = (steps >> 16) as usize;
+        let frac = (steps & 0xFFFF) as i64;
+        let i = idx & 63;
+
+        let (from, to) = if idx & 64 == 0 {
+            (SIN_TABLE[i], SIN_TABLE[i + 1])
+        } else {
+            (SIN_TABLE[64 - i], SIN_TABLE[63 - i])
+        };
+        let val = from + (((to - from) as i64 * frac) >> 16) as i32;
+
+        if idx & 128 == 0 {
+            Fx(val)
+        } else {
+            Fx(-val)
+        }
+    }
+
+    pub fn cos(self) -> Fx {
+        (self + Fx::FRAC_PI_2).sin()
+    }
+
+    /// Exact to the last bit, 0 for negative numbers.
+    pub fn sqrt(self) -> Fx {
+        if self.0 <= 0 {
+            return Fx::ZERO;
+        }
+        Fx(isqrt((self.0 as u64) << 16) as i32)
+    }
+}
+
+/// Bit by bit integer square root, no multiplies or divides.
+fn isqrt(mut n: u64) -> u64 {
+    let mut res = 0u64;
+    let mut bit = 1u64 << 62;
+    while bit > n {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if n >= res + bit {
+            n -= res + bit;
+            res = (res >> 1) + bit;
+        } else {
+            res >>= 1;
+        }
+        bit >>= 2;
+    }
+    res
+}
+
+impl From<i32> for Fx {
+    fn from(v: i32) -> Self {
+        Fx::from_int(v)
+    }
+}
+
+impl From<Fx> for f32 {
+    fn from(v: Fx) -> Self {
+        v.to_f32()
+    }
+}
+
+impl Add for Fx {
+    type Output = Fx;
+
+    fn add(self, rhs: Fx) -> Fx {
+        Fx(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Fx {
+    type Output = Fx;
+
+    fn sub(self, rhs: Fx) -> Fx {
+        Fx(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+/// Rounds to the nearest step, truncating would pull every product towards -inf and that adds
+/// up over frames.
+impl Mul for Fx {
+    type Output = Fx;
+
+    fn mul(self, rhs: Fx) -> Fx {
+        Fx(((self.0 as i64 * rhs.0 as i64 + (1 << 15)) >> 16) as i32)
+    }
+}
+
+impl Div for Fx {
+    type Output = Fx;
+
+    fn div(self, rhs: Fx) -> Fx {
+        Fx((((self.0 as i64) << 16) / rhs.0 as i64) as i32)
+    }
+}
+
+impl Rem for Fx {
+    type Output = Fx;
+
+    fn rem(self, rhs: Fx) -> Fx {
+        Fx(self.0.wrapping_rem(rhs.0))
+    }
+}
+
+impl Mul<i32> for Fx {
+    type Output = Fx;
+
+    fn mul(self, rhs: i32) -> Fx {
+        Fx(self.0.wrapping_mul(rhs))
+    }
+}
+
+impl Div<i32> for Fx {
+    type Output = Fx;
+
+    fn div(self, rhs: i32) -> Fx {
+        Fx(self.0.wrapping_div(rhs))
+    }
+}
+
+impl Neg for Fx {
+    type Output = Fx;
+
+    fn neg(self) -> Fx {
+        Fx(self.0.wrapping_neg())
+    }
+}
+
+impl AddAssign for Fx {
+    fn add_assign(&mut self, rhs: Fx) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fx {
+    fn sub_assign(&mut self, rhs: Fx) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Fx {
+    fn mul_assign(&mut self, rhs: Fx) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Fx {
+    fn div_assign(&mut self, rhs: Fx) {
+        *self = *self / rhs;
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Hash)]
+pub struct FxVec2 {
+    pub x: Fx,
+    pub y: Fx,
+}
+
+impl FxVec2 {
+    pub const ZERO: FxVec2 = FxVec2::new(Fx::ZERO, Fx::ZERO);
+
+    pub const fn new(x: Fx, y: Fx) -> Self {
+        Self { x, y }
+    }
+
+    /// Unit vector pointing at `angle` radians.
+    pub fn from_angle(angle: Fx) -> Self {
+        Self::new(angle.cos(), angle.sin())
+    }
+
+    pub fn dot(self, other: FxVec2) -> Fx {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length_sq(self) -> Fx {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> Fx {
+        self.length_sq().sqrt()
+    }
+
+    pub fn distance_sq(self, other: FxVec2) -> Fx {
+        (other - self).length_sq()
+    }
+
+    /// Nearest whole pixel, same as rounding each `f32` with `roundf`.
+    pub fn round(self) -> (i32, i32) {
+        (self.x.round(), self.y.round())
+    }
+}
+
+impl From<FxVec2> for Vec2 {
+    fn from(v: FxVec2) -> Self {
+        Vec2::new(v.x.to_f32(), v.y.to_f32())
+    }
+}
+
+impl Add for FxVec2 {
+    type Output = FxVec2;
+
+    fn add(self, rhs: FxVec2) -> FxVec2 {
+        FxVec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for FxVec2 {
+    type Output = FxVec2;
+
+    fn sub(self, rhs: FxVec2) -> FxVec2 {
+        FxVec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<Fx> for FxVec2 {
+    type Output = FxVec2;
+
+    fn mul(self, rhs: Fx) -> FxVec2 {
+        FxVec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for FxVec2 {
+    type Output = FxVec2;
+
+    fn neg(self) -> FxVec2 {
+        FxVec2::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for FxVec2 {
+    fn add_assign(&mut self, rhs: FxVec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for FxVec2 {
+    fn sub_assign(&mut self, rhs: FxVec2) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Fx, b: f32, tolerance: f32) -> bool {
+        (a.to_f32() - b).abs() <= tolerance
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Fx::from_f32(1.5);
+        let b = Fx::from_int(-2);
+        assert_eq!(a + b, Fx::from_f32(-0.5));
+        assert_eq!(a * b, Fx::from_int(-3));
+        assert_eq!(b / a, Fx::from_f32(-4.0 / 3.0));
+        assert_eq!(
+            Fx::from_f32(70.5).rem_euclid(Fx::from_int(64)),
+            Fx::from_f32(6.5)
+        );
+        assert_eq!(
+            Fx::from_f32(-0.5).rem_euclid(Fx::from_int(64)),
+            Fx::from_f32(63.5)
+        );
+    }
+
+    #[test]
+    fn rounding_matches_libm() {
+        for v in [
+            -2.5, -1.5, -0.5, -0.49, 0.0, 0.49, 0.5, 1.5, 2.5, 31.7, -31.7,
+        ] {
+            assert_eq!(Fx::from_f32(v).round(), libm::roundf(v) as i32, "{v}");
+        }
+        assert_eq!(Fx::from_f32(-0.25).floor(), -1);
+    }
+
+    #[test]
+    fn trig() {
+        let mut angle = -10.0f32;
+        while angle < 10.0 {
+            let fx = Fx::from_f32(angle);
+            assert!(close(fx.sin(), libm::sinf(angle), 2e-4), "sin {angle}");
+            assert!(close(fx.cos(), libm::cosf(angle), 2e-4), "cos {angle}");
+            angle += 0.01;
+        }
+    }
+
+    #[test]
+    fn overflow_wraps() {
+        let step = Fx::from_raw(1);
+        assert_eq!(Fx::MAX + step, Fx::MIN);
+        assert_eq!(Fx::MIN - step, Fx::MAX);
+        assert_eq!(-Fx::MIN, Fx::MIN);
+        assert_eq!(Fx::MIN.abs(), Fx::MIN);
+        assert_eq!(Fx::MAX * 2, Fx::from_raw(-2));
+        assert_eq!(Fx::MIN / -1, Fx::MIN);
+        assert_eq!(Fx::MIN % Fx::from_raw(-1), Fx::ZERO);
+        assert_eq!(Fx::from_int(32768), Fx::MIN);
+        // Keeps the low 32 bits of the product, like the others
+        assert_eq!(Fx::from_int(256) * Fx::from_int(128), Fx::MIN);
+    }
+
+    #[test]
+    fn worm_path_matches_f32() {
+        // The worm's head steering around for half a minute. Fx can't land on exactly the same
+        // pixels, where the f32 path is a hair either side of a half the two round apart, but it
+        // should stay a small fraction of a pixel away and never be more than a pixel out.
+        let (mut dir, mut speed) = (0.0f32, 0.5f32);
+        let (mut x, mut y) = (32.0f32, 64.0f32);
+        let (mut fx_dir, mut fx_speed) = (Fx::ZERO, Fx::from_f32(0.5));
+        let mut head = FxVec2::new(Fx::from_int(32), Fx::from_int(64));
+        // Distance on a wrapping axis
+        let apart = |a: f32, b: f32, size: f32| {
+            let d = (a - b).abs() % size;
+            d.min(size - d)
+        };
+
+        for frame in 0..2000 {
+            let turn = match frame % 90 {
+                0..=29 => -1,
+                30..=49 => 1,
+                _ => 0,
+            };
+            dir += turn as f32 * 0.2 * (speed / 2.0);
+            fx_dir += Fx::from_f32(0.2) * (fx_speed / 2) * turn;
+            if frame % 200 == 199 {
+                speed += 0.035;
+                fx_speed += Fx::from_f32(0.035);
+            }
+
+            x = (x + speed * libm::cosf(dir) + 64.0) % 64.0;
+            y = (y + speed * libm::sinf(dir) + 128.0) % 128.0;
+            head += FxVec2::from_angle(fx_dir) * fx_speed;
+            head = FxVec2::new(
+                head.x.rem_euclid(Fx::from_int(64)),
+                head.y.rem_euclid(Fx::from_int(128)),
+            );
+
+            let (fx_x, fx_y) = (head.x.to_f32(), head.y.to_f32());
+            assert!(apart(fx_x, x, 64.0) < 0.05, "frame {frame} x {fx_x} {x}");
+            assert!(apart(fx_y, y, 128.0) < 0.05, "frame {frame} y {fx_y} {y}");
+            let (px, py) = head.round();
+            let float = (libm::roundf(x), libm::roundf(y));
+            assert!(apart(px as f32, float.0, 64.0) <= 1.0, "frame {frame}");
+            assert!(apart(py as f32, float.1, 128.0) <= 1.0, "frame {frame}");
+        }
+    }
+
+    #[test]
+    fn sqrt() {
+        for v in [0.0, 0.25, 1.0, 2.0, 12.25, 100.0, 30000.0] {
+            assert!(close(Fx::from_f32(v).sqrt(), libm::sqrtf(v), 1e-4), "{v}");
+        }
+        assert_eq!(Fx::from_int(-4).sqrt(), Fx::ZERO);
+        assert_eq!(
+            FxVec2::new(Fx::from_int(3), Fx::from_int(4)).length(),
+            Fx::from_int(5)
+        );
+    }
+}
@@ -1,5 +1,11 @@
 #![cfg_attr(not(test), no_std)]
 
+pub mod collision;
+pub mod fixed;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod num_text;
+pub mod tween;
 pub mod util;
 
 use embedded_graphics::draw_target::DrawTarget;
@@ -9,6 +15,12 @@ pub trait FruggerEngine<C> {
     fn draw_frame<T>(&mut self, display: &mut T)
     where
         T: DrawTarget<Color = C>;
+
+    /// How many times to call `draw_frame` and flush the display per update, for engines that
+    /// build one frame from several flushes.
+    fn subframes(&self) -> u8 {
+        1
+    }
 }
 
 #[derive(Default, Eq, PartialEq)]
@@ -56,6 +68,22 @@ pub struct FrugInputs {
     pub down: ButtonState,
 }
 
+impl FrugInputs {
+    /// Any button pressed this frame.
+    pub fn any_pressed(&self) -> bool {
+        [
+            &self.a,
+            &self.b,
+            &self.left,
+            &self.right,
+            &self.up,
+            &self.down,
+        ]
+        .iter()
+        .any(|button| button.pressed())
+    }
+}
+
 pub enum Orientation {
     Landscape,
     Portrait,
@@ -0,0 +1,240 @@
+//! Stand-ins for the hardware, for testing on the host. Needs the `mock` feature, which brings
+//! in `std`.
+
+extern crate std;
+
+use std::vec;
+use std::vec::Vec;
+
+use core::cell::Cell;
+use core::convert::Infallible;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+use crate::FrugTimer;
+
+/// A call that reached the display.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Call {
+    /// `draw_iter` with this many pixels.
+    Pixels(usize),
+    Contiguous(Rectangle),
+    Solid(Rectangle),
+    Clear,
+}
+
+/// Display that logs every call made on it and keeps the image they add up to, to check what an
+/// engine's `draw_frame` pushes against what it meant to show.
+pub struct Recorder<C> {
+    size: Size,
+    pixels: Vec<C>,
+    calls: Vec<Call>,
+    pushed: usize,
+    unchanged: usize,
+    outside: usize,
+}
+
+impl<C: PixelColor> Recorder<C> {
+    /// Starts out all `color`, like a display that was cleared on init.
+    pub fn new(size: Size, color: C) -> Self {
+        Self {
+            size,
+            pixels: vec![color; (size.width * size.height) as usize],
+            calls: Vec::new(),
+            pushed: 0,
+            unchanged: 0,
+            outside: 0,
+        }
+    }
+
+    pub fn get(&self, p: Point) -> Option<C> {
+        self.index(p).map(|idx| self.pixels[idx])
+    }
+
+    /// The image, row by row.
+    pub fn pixels(&self) -> &[C] {
+        &self.pixels
+    }
+
+    /// Calls since the counts were last reset.
+    pub fn calls(&self) -> &[Call] {
+        &self.calls
+    }
+
+    /// Pixels written on the display.
+    pub fn pushed(&self) -> usize {
+        self.pushed
+    }
+
+    /// Pixels written with the color they already were, wasted on a real display.
+    pub fn unchanged(&self) -> usize {
+        self.unchanged
+    }
+
+    /// Pixels that missed the display.
+    pub fn outside(&self) -> usize {
+        self.outside
+    }
+
+    /// Start logging and counting again, call between frames to see one at a time.
+    pub fn reset_counts(&mut self) {
+        self.calls.clear();
+        self.pushed = 0;
+        self.unchanged = 0;
+        self.outside = 0;
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        let Size { width, height } = self.size;
+        (p.x >= 0 && p.y >= 0 && (p.x as u32) < width && (p.y as u32) < height)
+            .then(|| (p.y as u32 * width + p.x as u32) as usize)
+    }
+
+    fn set(&mut self, p: Point, color: C) {
+        let Some(idx) = self.index(p) else {
+            self.outside += 1;
+            return;
+        };
+        self.pushed += 1;
+        if self.pixels[idx] == color {
+            self.unchanged += 1;
+        }
+        self.pixels[idx] = color;
+    }
+}
+
+impl<C: PixelColor> Dimensions for Recorder<C> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), self.size)
+    }
+}
+
+impl<C: PixelColor> DrawTarget for Recorder<C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mut count = 0;
+        for Pixel(p, color) in pixels {
+            self.set(p, color);
+            count += 1;
+        }
+        self.calls.push(Call::Pixels(count));
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.calls.push(Call::Contiguous(*area));
+        area.points()
+            .zip(colors)
+            .for_each(|(p, color)| self.set(p, color));
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.calls.push(Call::Solid(*area));
+        area.points().for_each(|p| self.set(p, color));
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.calls.push(Call::Clear);
+        let area = self.bounding_box();
+        area.points().for_each(|p| self.set(p, color));
+        Ok(())
+    }
+}
+
+/// Clock that only moves when told to. Ticks are µs, like the rp2040 timer.
+#[derive(Default)]
+pub struct MockTimer {
+    now: Cell<u64>,
+    step: u64,
+    delays: Vec<u64>,
+}
+
+impl MockTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the clock on by `us` after every read, as if each took that long.
+    pub fn auto_advance(mut self, us: u64) -> Self {
+        self.step = us;
+        self
+    }
+
+    pub fn advance(&self, us: u64) {
+        self.now.set(self.now.get() + us);
+    }
+
+    /// Reads the clock without moving it.
+    pub fn now(&self) -> u64 {
+        self.now.get()
+    }
+
+    /// Every `delay_ms`, in ms.
+    pub fn delays(&self) -> &[u64] {
+        &self.delays
+    }
+}
+
+impl FrugTimer for MockTimer {
+    fn ticks(&self) -> u64 {
+        let now = self.now.get();
+        self.advance(self.step);
+        now
+    }
+
+    /// Returns straight away with the clock moved on.
+    fn delay_ms(&mut self, ms: u64) {
+        self.delays.push(ms);
+        self.advance(ms * 1000);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    #[test]
+    fn records() {
+        let mut display = Recorder::new(Size::new(4, 2), BinaryColor::Off);
+        let area = Rectangle::new(Point::new(2, 0), Size::new(3, 1));
+        display.fill_solid(&area, BinaryColor::On).unwrap();
+        display
+            .draw_iter([Pixel(Point::new(2, 0), BinaryColor::On)])
+            .unwrap();
+
+        assert_eq!(display.calls(), &[Call::Solid(area), Call::Pixels(1)]);
+        assert_eq!(display.pushed(), 3);
+        assert_eq!(display.unchanged(), 1);
+        assert_eq!(display.outside(), 1);
+        assert_eq!(display.get(Point::new(3, 0)), Some(BinaryColor::On));
+        assert_eq!(display.get(Point::new(1, 0)), Some(BinaryColor::Off));
+        assert_eq!(display.get(Point::new(4, 0)), None);
+
+        display.reset_counts();
+        assert!(display.calls().is_empty());
+        assert_eq!(display.pushed(), 0);
+    }
+
+    #[test]
+    fn timer() {
+        let mut timer = MockTimer::new().auto_advance(5);
+        assert_eq!(timer.ticks(), 0);
+        assert_eq!(timer.ticks(), 5);
+        timer.advance(100);
+        timer.delay_ms(2);
+        assert_eq!(timer.now(), 2110);
+        assert_eq!(timer.ticks(), 2110);
+        assert_eq!(timer.delays(), &[2]);
+    }
+}
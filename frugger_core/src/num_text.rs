@@ -0,0 +1,158 @@
+/// Padding for numbers narrower than their width.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Pad {
+    Spaces,
+    /// Zeros go after the sign.
+    Zeros,
+}
+
+/// Short text built in a fixed buffer, for drawing numbers without `core::fmt`. Anything past
+/// `CAPACITY` bytes is dropped.
+#[derive(Copy, Clone)]
+pub struct NumText {
+    buf: [u8; NumText::CAPACITY],
+    len: usize,
+}
+
+impl NumText {
+    pub const CAPACITY: usize = 16;
+
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; Self::CAPACITY],
+            len: 0,
+        }
+    }
+
+    pub fn num(value: i32) -> Self {
+        Self::padded(value, 0, Pad::Spaces)
+    }
+
+    pub fn padded(value: i32, width: usize, pad: Pad) -> Self {
+        let mut text = Self::new();
+        text.push_num(value, width, pad);
+        text
+    }
+
+    /// `M:SS`.
+    pub fn time(seconds: u32) -> Self {
+        let mut text = Self::new();
+        text.push_num((seconds / 60) as i32, 0, Pad::Spaces);
+        text.push_str(":");
+        text.push_num((seconds % 60) as i32, 2, Pad::Zeros);
+        text
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only whole ASCII characters are ever pushed
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    fn push_byte(&mut self, byte: u8) {
+        if self.len < Self::CAPACITY {
+            self.buf[self.len] = byte;
+            self.len += 1;
+        }
+    }
+
+    /// Non-ASCII characters become `?`.
+    pub fn push_str(&mut self, text: &str) -> &mut Self {
+        for c in text.chars() {
+            self.push_byte(if c.is_ascii() { c as u8 } else { b'?' });
+        }
+        self
+    }
+
+    /// `value` right aligned in at least `width` characters, counting the sign.
+    pub fn push_num(&mut self, value: i32, width: usize, pad: Pad) -> &mut Self {
+        let mut digits = [0u8; 10];
+        let mut count = 0;
+        let mut rest = value.unsigned_abs();
+        loop {
+            digits[count] = b'0' + (rest % 10) as u8;
+            count += 1;
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+
+        let used = count + usize::from(value < 0);
+        let padding = width.saturating_sub(used);
+        if pad == Pad::Spaces {
+            (0..padding).for_each(|_| self.push_byte(b' '));
+        }
+        if value < 0 {
+            self.push_byte(b'-');
+        }
+        if pad == Pad::Zeros {
+            (0..padding).for_each(|_| self.push_byte(b'0'));
+        }
+        digits[..count]
+            .iter()
+            .rev()
+            .for_each(|digit| self.push_byte(*digit));
+        self
+    }
+
+    /// `byte` `count` times, for tallies like lives.
+    pub fn push_repeat(&mut self, byte: u8, count: usize) -> &mut Self {
+        (0..count).for_each(|_| self.push_byte(byte));
+        self
+    }
+}
+
+impl Default for NumText {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(NumText::num(0).as_str(), "0");
+        assert_eq!(NumText::num(1234).as_str(), "1234");
+        assert_eq!(NumText::num(-56).as_str(), "-56");
+        assert_eq!(NumText::num(i32::MIN).as_str(), "-2147483648");
+        assert_eq!(NumText::padded(42, 5, Pad::Spaces).as_str(), "   42");
+        assert_eq!(NumText::padded(-7, 4, Pad::Zeros).as_str(), "-007");
+        assert_eq!(NumText::padded(12345, 3, Pad::Zeros).as_str(), "12345");
+    }
+
+    #[test]
+    fn time_and_labels() {
+        assert_eq!(NumText::time(0).as_str(), "0:00");
+        assert_eq!(NumText::time(125).as_str(), "2:05");
+
+        let mut text = NumText::new();
+        text.push_str("LV").push_num(3, 2, Pad::Zeros);
+        assert_eq!(text.as_str(), "LV03");
+    }
+
+    #[test]
+    fn truncates() {
+        let mut text = NumText::new();
+        text.push_str("SCORE ")
+            .push_num(1_000_000_000, 0, Pad::Spaces);
+        assert_eq!(text.len(), NumText::CAPACITY);
+        assert_eq!(text.as_str(), "SCORE 1000000000");
+        text.push_repeat(b'*', 3);
+        assert_eq!(text.as_str(), "SCORE 1000000000");
+    }
+}
@@ -0,0 +1,290 @@
+//! Values eased between two points over a number of frames, for sliding menus, popping text and
+//! the like. Tick once per update and read the value back.
+
+use core::f32::consts::TAU;
+use libm::{powf, roundf, sinf};
+
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+pub enum Ease {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    /// Hits the end and bounces back a few times, like a dropped ball.
+    BounceOut,
+    /// Overshoots and springs back.
+    ElasticOut,
+}
+
+impl Ease {
+    /// Map progress `t` in 0..=1 to eased progress. 0 and 1 map to themselves, elastic overshoots
+    /// in between.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::QuadIn => t * t,
+            Ease::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Ease::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    let u = -2.0 * t + 2.0;
+                    1.0 - u * u / 2.0
+                }
+            }
+            Ease::BounceOut => bounce_out(t),
+            Ease::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    powf(2.0, -10.0 * t) * sinf((t * 10.0 - 0.75) * (TAU / 3.0)) + 1.0
+                }
+            }
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+/// `from` to `to` over `frames` ticks.
+#[derive(Copy, Clone, Debug)]
+pub struct Tween {
+    from: f32,
+    to: f32,
+    frames: u16,
+    frame: u16,
+    ease: Ease,
+}
+
+impl Tween {
+    pub const fn new(from: f32, to: f32, frames: u16, ease: Ease) -> Self {
+        Self {
+            from,
+            to,
+            frames,
+            frame: 0,
+            ease,
+        }
+    }
+
+    /// Already finished, sitting at `value`.
+    pub const fn idle(value: f32) -> Self {
+        Self::new(value, value, 0, Ease::Linear)
+    }
+
+    /// Advance a frame and return the new value.
+    pub fn tick(&mut self) -> f32 {
+        if self.frame < self.frames {
+            self.frame += 1;
+        }
+        self.value()
+    }
+
+    pub fn value(&self) -> f32 {
+        lerp(self.from, self.to, self.ease.apply(self.progress()))
+    }
+
+    /// Value rounded to the nearest pixel.
+    pub fn round(&self) -> i32 {
+        roundf(self.value()) as i32
+    }
+
+    /// 0 to 1, before easing.
+    pub fn progress(&self) -> f32 {
+        if self.frames == 0 {
+            1.0
+        } else {
+            self.frame as f32 / self.frames as f32
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.frame >= self.frames
+    }
+
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    pub fn restart(&mut self) {
+        self.frame = 0;
+    }
+
+    /// Head for `to` from wherever the tween is now, so it can be redirected mid flight.
+    pub fn retarget(&mut self, to: f32, frames: u16, ease: Ease) {
+        *self = Self::new(self.value(), to, frames, ease);
+    }
+}
+
+/// One leg of a `Sequence`, starting from where the last one ended.
+#[derive(Copy, Clone, Debug)]
+pub struct Step {
+    pub to: f32,
+    pub frames: u16,
+    pub ease: Ease,
+}
+
+impl Step {
+    pub const fn new(to: f32, frames: u16, ease: Ease) -> Self {
+        Self { to, frames, ease }
+    }
+
+    /// Stay at `value` for `frames`.
+    pub const fn hold(value: f32, frames: u16) -> Self {
+        Self::new(value, frames, Ease::Linear)
+    }
+}
+
+/// Tweens chained end to end. Steps are usually a `const` slice.
+#[derive(Copy, Clone, Debug)]
+pub struct Sequence<'a> {
+    start: f32,
+    steps: &'a [Step],
+    idx: usize,
+    tween: Tween,
+    looping: bool,
+}
+
+impl<'a> Sequence<'a> {
+    pub fn new(start: f32, steps: &'a [Step]) -> Self {
+        Self {
+            start,
+            steps,
+            idx: 0,
+            tween: Self::step(start, steps.first()),
+            looping: false,
+        }
+    }
+
+    /// Go back to the first step after the last. The last step should end at `start` to loop
+    /// smoothly.
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    fn step(from: f32, step: Option<&Step>) -> Tween {
+        match step {
+            Some(step) => Tween::new(from, step.to, step.frames, step.ease),
+            None => Tween::idle(from),
+        }
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        self.tween.tick();
+
+        if self.tween.finished() {
+            if self.idx + 1 < self.steps.len() {
+                self.idx += 1;
+                self.tween = Self::step(self.tween.target(), self.steps.get(self.idx));
+            } else if self.looping && !self.steps.is_empty() {
+                self.idx = 0;
+                self.tween = Self::step(self.start, self.steps.first());
+            }
+        }
+
+        self.value()
+    }
+
+    pub fn value(&self) -> f32 {
+        self.tween.value()
+    }
+
+    pub fn round(&self) -> i32 {
+        self.tween.round()
+    }
+
+    /// Index of the step playing.
+    pub fn current(&self) -> usize {
+        self.idx
+    }
+
+    /// Never true for a looping sequence.
+    pub fn finished(&self) -> bool {
+        !self.looping && self.idx + 1 >= self.steps.len() && self.tween.finished()
+    }
+
+    pub fn restart(&mut self) {
+        self.idx = 0;
+        self.tween = Self::step(self.start, self.steps.first());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eases_hit_both_ends() {
+        for ease in [
+            Ease::Linear,
+            Ease::QuadIn,
+            Ease::QuadOut,
+            Ease::QuadInOut,
+            Ease::BounceOut,
+            Ease::ElasticOut,
+        ] {
+            assert!(ease.apply(0.0).abs() < 1e-5, "{ease:?}");
+            assert!((ease.apply(1.0) - 1.0).abs() < 1e-5, "{ease:?}");
+        }
+        assert!(Ease::ElasticOut.apply(0.2) > 1.0);
+    }
+
+    #[test]
+    fn tween_runs_its_frames() {
+        let mut tween = Tween::new(10.0, 20.0, 4, Ease::Linear);
+        assert_eq!(tween.value(), 10.0);
+        assert_eq!(tween.tick(), 12.5);
+        tween.tick();
+        tween.tick();
+        assert!(!tween.finished());
+        assert_eq!(tween.tick(), 20.0);
+        assert!(tween.finished());
+        assert_eq!(tween.tick(), 20.0);
+
+        tween.retarget(0.0, 2, Ease::Linear);
+        assert_eq!(tween.tick(), 10.0);
+    }
+
+    #[test]
+    fn sequence_chains_and_loops() {
+        const STEPS: [Step; 2] = [
+            Step::new(4.0, 2, Ease::Linear),
+            Step::new(0.0, 4, Ease::Linear),
+        ];
+
+        let mut once = Sequence::new(0.0, &STEPS);
+        let values: [f32; 6] = core::array::from_fn(|_| once.tick());
+        assert_eq!(values, [2.0, 4.0, 3.0, 2.0, 1.0, 0.0]);
+        assert!(once.finished());
+
+        let mut looped = Sequence::new(0.0, &STEPS).looping();
+        for _ in 0..6 {
+            looped.tick();
+        }
+        assert!(!looped.finished());
+        assert_eq!(looped.tick(), 2.0);
+    }
+}
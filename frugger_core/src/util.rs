@@ -23,11 +23,52 @@ impl<'a> Bencher<'a> {
         self.last = self.timer.ticks();
     }
 
-    pub fn cp(&mut self, msg: &str) {
+    /// Ticks since the last checkpoint or start.
+    pub fn cp(&mut self, msg: &str) -> u64 {
         let end = self.timer.ticks();
         let time = end - self.last;
         // log!("{msg}: {time}ms");
-        self.last = self.timer.ticks();
+        self.last = end;
+        time
+    }
+}
+
+/// Keeps the game loop at a steady frame rate by sleeping out what is left of each frame.
+pub struct FrameClock {
+    /// Frame length in ticks.
+    frame: u64,
+    start: u64,
+}
+
+impl FrameClock {
+    /// Ticks are µs.
+    pub fn new(fps: u64, timer: &dyn FrugTimer) -> Self {
+        Self {
+            frame: 1_000_000 / fps,
+            start: timer.ticks(),
+        }
+    }
+
+    /// Ticks since the frame started.
+    pub fn elapsed(&self, timer: &dyn FrugTimer) -> u64 {
+        timer.ticks() - self.start
+    }
+
+    /// Waits for the frame to be over and starts the next one on time, so frames keep to the rate
+    /// however the waits round. A frame that ran over by less than a frame is made up by the next
+    /// one, longer than that the next one starts straight away without trying to catch up.
+    pub fn end_frame(&mut self, timer: &mut dyn FrugTimer) {
+        let deadline = self.start + self.frame;
+        let now = timer.ticks();
+        if now < deadline {
+            // Whole ms only, round up so the frame isn't short
+            timer.delay_ms((deadline - now).div_ceil(1000));
+            self.start = deadline;
+        } else if now - deadline < self.frame {
+            self.start = deadline;
+        } else {
+            self.start = now;
+        }
     }
 }
 
@@ -50,3 +91,122 @@ impl RollingAverage {
         self.sum / 10
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockTimer;
+
+    #[test]
+    fn rolling_average() {
+        let mut avg = RollingAverage::new();
+        for val in 1..=10 {
+            avg.add(val * 10);
+        }
+        assert_eq!(avg.average(), 55);
+
+        // Oldest out first
+        avg.add(110);
+        assert_eq!(avg.average(), 65);
+        for _ in 0..10 {
+            avg.add(7);
+        }
+        assert_eq!(avg.average(), 7);
+    }
+
+    #[test]
+    fn rolling_average_of_frame_times() {
+        let timer = MockTimer::new();
+        let mut avg = RollingAverage::new();
+        for frame in 0..20 {
+            let start = timer.ticks();
+            timer.advance(if frame % 2 == 0 { 1000 } else { 3000 });
+            avg.add(timer.ticks() - start);
+        }
+        assert_eq!(avg.average(), 2000);
+    }
+
+    #[test]
+    fn bencher_checkpoints() {
+        // Each read of the clock takes 1 tick
+        let timer = MockTimer::new().auto_advance(1);
+        let mut bench = Bencher::new(&timer);
+        bench.start();
+        timer.advance(500);
+        assert_eq!(bench.cp("logic"), 501);
+        timer.advance(2000);
+        assert_eq!(bench.cp("draw"), 2001);
+        assert_eq!(bench.cp("nothing"), 1);
+    }
+
+    #[test]
+    fn frame_clock_sleeps_out_the_frame() {
+        let mut timer = MockTimer::new();
+        let mut clock = FrameClock::new(60, &timer);
+
+        // 16_666 µs a frame
+        timer.advance(4_000);
+        assert_eq!(clock.elapsed(&timer), 4_000);
+        clock.end_frame(&mut timer);
+        assert_eq!(timer.delays(), &[13]);
+        // Rounding up the wait went over, the next frame started on time
+        assert_eq!(clock.elapsed(&timer), 334);
+
+        // Over by the same again, it doesn't add up
+        timer.advance(3_666);
+        clock.end_frame(&mut timer);
+        assert_eq!(timer.delays(), &[13, 13]);
+        assert_eq!(clock.elapsed(&timer), 334);
+    }
+
+    #[test]
+    fn frame_clock_catches_up() {
+        let mut timer = MockTimer::new();
+        let mut clock = FrameClock::new(60, &timer);
+        timer.advance(20_000);
+        clock.end_frame(&mut timer);
+        assert!(timer.delays().is_empty());
+
+        // Less than a frame over comes out of the next frame
+        timer.advance(10_000);
+        clock.end_frame(&mut timer);
+        assert_eq!(timer.delays(), &[4]);
+        assert_eq!(clock.elapsed(&timer), 668);
+    }
+
+    #[test]
+    fn frame_clock_long_frames() {
+        let mut timer = MockTimer::new();
+        let mut clock = FrameClock::new(60, &timer);
+        timer.advance(40_000);
+        clock.end_frame(&mut timer);
+        assert!(timer.delays().is_empty());
+
+        // The next frame gets all of its time, not what was lost
+        timer.advance(10_000);
+        clock.end_frame(&mut timer);
+        assert_eq!(timer.delays(), &[7]);
+        assert_eq!(timer.now(), 57_000);
+    }
+
+    #[test]
+    fn frame_clock_steady_rate() {
+        // Game loop with uneven work, every 60 frames take a second to within a ms. A frame is
+        // 16_666 µs, so a second is 999_960
+        let mut timer = MockTimer::new().auto_advance(3);
+        let mut clock = FrameClock::new(60, &timer);
+        for second in 1..=10 {
+            for frame in 0..60 {
+                timer.advance(2_000 + (frame % 7) * 1_500);
+                clock.end_frame(&mut timer);
+            }
+            let expected = second * 999_960;
+            assert!(
+                (expected..expected + 1_000).contains(&timer.now()),
+                "{second}: {}",
+                timer.now()
+            );
+        }
+        assert_eq!(timer.delays().len(), 600);
+    }
+}
@@ -0,0 +1,334 @@
+//! This build script converts the images in `assets/` into packed `Sprite`
+//! constants, written to `$OUT_DIR/assets.rs` and pulled in by `src/assets.rs`.
+//!
+//! - `name.png` or `name.bmp` becomes `NAME: Sprite`. Pixels brighter than
+//!   half are On.
+//! - Transparent PNG pixels (alpha under half) become the sprite's mask, or a
+//!   `name_mask.png`/`name_mask.bmp` of the same size can be given instead.
+//! - `name.16x8.png` is a sheet of 16x8 frames and becomes `NAME: SpriteSheet`.
+//!
+//! Images (frames for sheets) have to fit on the screen in either orientation,
+//! the build fails otherwise. The flash used by the packed data is written to
+//! `$OUT_DIR/asset_report.txt`, set `FRUGGER_ASSET_REPORT=1` to print it.
+//!
+//! Fonts live in `assets/fonts/` and go to `$OUT_DIR/fonts.rs`:
+//!
+//! - `name.WxH.png` is a sheet of WxH glyphs, 16 to a row, and becomes
+//!   `FONT_NAME: MonoFont`.
+//! - `name.WxH.prop.png` is the same but proportional, each glyph is as wide
+//!   as its rightmost lit column. It becomes `FONT_NAME: PropFont`.
+//! - Glyphs are printable ASCII from space, or the characters in `name.txt` in
+//!   sheet order.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tinybmp::Bmp;
+
+struct Image {
+    width: u32,
+    height: u32,
+    on: Vec<bool>,
+    mask: Option<Vec<bool>>,
+}
+
+fn main() {
+    let dir = Path::new("assets");
+    println!("cargo:rerun-if-changed=assets");
+    println!("cargo:rerun-if-env-changed=FRUGGER_ASSET_REPORT");
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|p| matches!(extension(p).as_str(), "png" | "bmp"));
+    paths.sort();
+
+    let mut out = String::new();
+    let mut report = String::new();
+    let mut total = 0;
+
+    for path in &paths {
+        let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+        if stem.ends_with("_mask") {
+            continue;
+        }
+
+        let (name, frame) = match stem.split_once('.') {
+            Some((name, frame)) => (name.to_string(), Some(parse_frame(path, frame))),
+            None => (stem.clone(), None),
+        };
+
+        let mut img = load(path);
+        if let Some(mask_path) = ["png", "bmp"]
+            .iter()
+            .map(|ext| path.with_file_name(format!("{stem}_mask.{ext}")))
+            .find(|p| p.exists())
+        {
+            let mask = load(&mask_path);
+            if (mask.width, mask.height) != (img.width, img.height) {
+                panic!("{} is a different size to its image", mask_path.display());
+            }
+            img.mask = Some(mask.on);
+        }
+
+        let (check_w, check_h) = frame.unwrap_or((img.width, img.height));
+        if !fits_screen(check_w, check_h) {
+            panic!(
+                "{} is {check_w}x{check_h}, which doesn't fit on a 128x64 screen",
+                path.display()
+            );
+        }
+        if let Some((fw, fh)) = frame {
+            if img.width % fw != 0 || img.height % fh != 0 {
+                panic!(
+                    "{} isn't a whole number of {fw}x{fh} frames",
+                    path.display()
+                );
+            }
+        }
+
+        let data = pack(&img.on, img.width);
+        let mask = img.mask.as_ref().map(|mask| pack(mask, img.width));
+        let size = data.len() + mask.as_ref().map_or(0, Vec::len);
+        total += size;
+        writeln!(
+            report,
+            "{:<32} {}x{} {size} bytes",
+            name, img.width, img.height
+        )
+        .unwrap();
+
+        let const_name = name.to_uppercase().replace(['-', ' '], "_");
+        let mut sprite = format!(
+            "Sprite::new({}, {}, 0, 0, &{data:?})",
+            img.width, img.height
+        );
+        if let Some(mask) = mask {
+            write!(sprite, ".with_mask(&{mask:?})").unwrap();
+        }
+
+        match frame {
+            Some((fw, fh)) => writeln!(
+                out,
+                "pub const {const_name}: SpriteSheet = SpriteSheet::new({sprite}, Frames::Grid(Size::new({fw}, {fh})));"
+            ),
+            None => writeln!(out, "pub const {const_name}: Sprite = {sprite};"),
+        }
+        .unwrap();
+    }
+
+    let fonts = fonts(&dir.join("fonts"), &mut report, &mut total);
+
+    writeln!(out, "pub const ASSET_BYTES: usize = {total};").unwrap();
+    writeln!(report, "total {total} bytes").unwrap();
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("assets.rs"), out).unwrap();
+    fs::write(out_dir.join("fonts.rs"), fonts).unwrap();
+    fs::write(out_dir.join("asset_report.txt"), &report).unwrap();
+
+    if env::var("FRUGGER_ASSET_REPORT").is_ok_and(|v| v == "1") {
+        for line in report.lines() {
+            println!("cargo:warning={line}");
+        }
+    }
+}
+
+fn fonts(dir: &Path, report: &mut String, total: &mut usize) -> String {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|p| matches!(extension(p).as_str(), "png" | "bmp"));
+    paths.sort();
+
+    let mut out = String::new();
+    for path in &paths {
+        let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+        let mut parts = stem.split('.');
+        let name = parts.next().unwrap().to_string();
+        let (cw, ch) = parts
+            .next()
+            .map(|cell| parse_frame(path, cell))
+            .unwrap_or_else(|| panic!("{}: expected name.WxH for a font", path.display()));
+        let prop = parts.next() == Some("prop");
+
+        let chars = match fs::read_to_string(dir.join(format!("{name}.txt"))) {
+            Ok(chars) => chars.trim_end_matches(['\n', '\r']).to_string(),
+            Err(_) => (' '..='~').collect(),
+        };
+
+        let img = load(path);
+        if !fits_screen(cw, ch) || img.width % cw != 0 || img.height % ch != 0 {
+            panic!("{} isn't a sheet of {cw}x{ch} glyphs", path.display());
+        }
+        let cols = (img.width / cw) as usize;
+        if chars.chars().count() > cols * (img.height / ch) as usize {
+            panic!("{} has fewer glyphs than characters", path.display());
+        }
+
+        // Pixel of glyph `idx` at `x`, `y` within it
+        let lit = |idx: usize, x: u32, y: u32| {
+            let px = (idx % cols) as u32 * cw + x;
+            let py = (idx / cols) as u32 * ch + y;
+            img.on[(py * img.width + px) as usize]
+        };
+
+        // Lowest row used by the digits, so descenders hang below
+        let baseline = chars
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| c.is_ascii_digit())
+            .flat_map(|(idx, _)| (0..ch).filter(move |y| (0..cw).any(|x| lit(idx, x, *y))))
+            .max()
+            .unwrap_or(ch - 1);
+
+        let data = pack(&img.on, img.width);
+        *total += data.len();
+        writeln!(report, "{:<32} {cw}x{ch} font {} bytes", name, data.len()).unwrap();
+
+        let const_name = format!("FONT_{}", name.to_uppercase().replace(['-', ' '], "_"));
+        if prop {
+            let widths: Vec<u32> = (0..chars.chars().count())
+                .map(|idx| {
+                    (0..cw)
+                        .rev()
+                        .find(|x| (0..ch).any(|y| lit(idx, *x, y)))
+                        .map_or(cw.div_ceil(2), |x| x + 1)
+                })
+                .collect();
+            *total += widths.len();
+            writeln!(
+                out,
+                "pub const {const_name}: PropFont = PropFont::new(&{data:?}, {}, Size::new({cw}, {ch}), {chars:?}, &{widths:?}, {baseline});",
+                img.width
+            )
+            .unwrap();
+        } else {
+            // StrGlyphMapping treats a \0 as the start of a range
+            let mapping = if chars.chars().eq(' '..='~') {
+                "\"\\0 ~\"".to_string()
+            } else {
+                format!("{chars:?}")
+            };
+            let replacement = chars
+                .chars()
+                .position(|c| c == '?')
+                .or_else(|| chars.chars().position(|c| c == ' '))
+                .unwrap_or(0);
+            writeln!(
+                out,
+                "pub const {const_name}: MonoFont = MonoFont {{
+    image: ImageRaw::new(&{data:?}, {}),
+    glyph_mapping: &StrGlyphMapping::new({mapping}, {replacement}),
+    character_size: Size::new({cw}, {ch}),
+    character_spacing: 1,
+    baseline: {baseline},
+    underline: DecorationDimensions::new({}, 1),
+    strikethrough: DecorationDimensions::new({}, 1),
+}};",
+                img.width,
+                baseline + 1,
+                ch / 2,
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn parse_frame(path: &Path, frame: &str) -> (u32, u32) {
+    frame
+        .split_once('x')
+        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+        .unwrap_or_else(|| panic!("{}: expected name.WxH for a sheet", path.display()))
+}
+
+fn fits_screen(width: u32, height: u32) -> bool {
+    (width <= 128 && height <= 64) || (width <= 64 && height <= 128)
+}
+
+fn load(path: &Path) -> Image {
+    let bytes = fs::read(path).unwrap();
+    match extension(path).as_str() {
+        "png" => load_png(path, &bytes),
+        _ => load_bmp(path, &bytes),
+    }
+}
+
+fn load_png(path: &Path, bytes: &[u8]) -> Image {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+
+    let channels = info.color_type.samples();
+    let (mut on, mut mask) = (Vec::new(), Vec::new());
+    for px in buf[..info.buffer_size()].chunks(channels) {
+        let (luma, alpha) = match info.color_type {
+            png::ColorType::Grayscale => (px[0] as u32, 255),
+            png::ColorType::GrayscaleAlpha => (px[0] as u32, px[1]),
+            png::ColorType::Rgb => (luma(px[0], px[1], px[2]), 255),
+            png::ColorType::Rgba => (luma(px[0], px[1], px[2]), px[3]),
+            png::ColorType::Indexed => unreachable!("expanded by the decoder"),
+        };
+        on.push(luma >= 128);
+        mask.push(alpha >= 128);
+    }
+
+    Image {
+        width: info.width,
+        height: info.height,
+        on,
+        mask: mask.contains(&false).then_some(mask),
+    }
+}
+
+fn load_bmp(path: &Path, bytes: &[u8]) -> Image {
+    use embedded_graphics::pixelcolor::Rgb888;
+    use embedded_graphics::prelude::*;
+
+    let bmp =
+        Bmp::<Rgb888>::from_slice(bytes).unwrap_or_else(|e| panic!("{}: {e:?}", path.display()));
+    let size = bmp.size();
+    let mut on = vec![false; (size.width * size.height) as usize];
+    for Pixel(p, col) in bmp.pixels() {
+        on[(p.y as u32 * size.width + p.x as u32) as usize] =
+            luma(col.r(), col.g(), col.b()) >= 128;
+    }
+
+    Image {
+        width: size.width,
+        height: size.height,
+        on,
+        mask: None,
+    }
+}
+
+fn luma(r: u8, g: u8, b: u8) -> u32 {
+    (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000
+}
+
+/// One bit per pixel, MSB first, each row padded to a whole byte.
+fn pack(pixels: &[bool], width: u32) -> Vec<u8> {
+    let stride = width.div_ceil(8) as usize;
+    let mut out = Vec::new();
+    for row in pixels.chunks(width as usize) {
+        let mut bytes = vec![0u8; stride];
+        for (x, _) in row.iter().enumerate().filter(|(_, on)| **on) {
+            bytes[x / 8] |= 0x80 >> (x % 8);
+        }
+        out.extend(bytes);
+    }
+    out
+}
@@ -0,0 +1,306 @@
+use crate::util::{Flip, Rotation, Sprite};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// How frames are laid out in a sheet.
+pub enum Frames<'a> {
+    /// Equal sized frames, left to right then top to bottom.
+    Grid(Size),
+    /// Explicit frame rectangles, relative to the sheet's top left.
+    Rects(&'a [Rectangle]),
+}
+
+pub struct SpriteSheet<'a> {
+    sprite: Sprite<'a>,
+    frames: Frames<'a>,
+}
+
+impl<'a> SpriteSheet<'a> {
+    pub const fn new(sprite: Sprite<'a>, frames: Frames<'a>) -> Self {
+        Self { sprite, frames }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        match self.frames {
+            Frames::Grid(size) => {
+                let Size { width, height } = self.sprite.size();
+                ((width / size.width) * (height / size.height)) as usize
+            }
+            Frames::Rects(rects) => rects.len(),
+        }
+    }
+
+    pub fn frame(&self, idx: usize) -> Rectangle {
+        match self.frames {
+            Frames::Grid(size) => {
+                let cols = (self.sprite.size().width / size.width) as usize;
+                let x = (idx % cols) as u32 * size.width;
+                let y = (idx / cols) as u32 * size.height;
+                Rectangle::new(Point::new(x as _, y as _), size)
+            }
+            Frames::Rects(rects) => rects[idx],
+        }
+    }
+
+    /// Draw frame `idx` with its top left at `pos`.
+    pub fn draw_frame<D>(
+        &self,
+        idx: usize,
+        pos: Point,
+        flip: Flip,
+        rotation: Rotation,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        self.sprite
+            .draw_part(self.frame(idx), pos, flip, rotation, target)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Frame {
+    /// Index into the sheet.
+    pub idx: u8,
+    /// How many ticks to show it for.
+    pub ticks: u8,
+}
+
+impl Frame {
+    pub const fn new(idx: u8, ticks: u8) -> Self {
+        Self { idx, ticks }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Playback {
+    Loop,
+    /// Stop on the last frame.
+    Once,
+}
+
+pub struct Animation<'a> {
+    pub name: &'a str,
+    pub frames: &'a [Frame],
+    pub playback: Playback,
+}
+
+impl<'a> Animation<'a> {
+    /// Panics without frames, at compile time for constants.
+    pub const fn new(name: &'a str, frames: &'a [Frame], playback: Playback) -> Self {
+        assert!(!frames.is_empty(), "animation has no frames");
+        Self {
+            name,
+            frames,
+            playback,
+        }
+    }
+}
+
+/// Plays named animations from a sheet, tick it once per game update.
+pub struct Animator<'a> {
+    pub flip: Flip,
+    pub rotation: Rotation,
+    sheet: &'a SpriteSheet<'a>,
+    anims: &'a [Animation<'a>],
+    curr: usize,
+    frame: usize,
+    ticks: u8,
+    done: bool,
+}
+
+impl<'a> Animator<'a> {
+    /// Starts on the first animation. Panics if there are no animations, one has no frames or
+    /// shows a frame the sheet doesn't have.
+    pub fn new(sheet: &'a SpriteSheet<'a>, anims: &'a [Animation<'a>]) -> Self {
+        assert!(!anims.is_empty(), "animator has no animations");
+        for anim in anims {
+            assert!(!anim.frames.is_empty(), "animation has no frames");
+            assert!(
+                anim.frames
+                    .iter()
+                    .all(|frame| (frame.idx as usize) < sheet.frame_count()),
+                "animation shows a frame the sheet doesn't have"
+            );
+        }
+
+        Self {
+            flip: Flip::None,
+            rotation: Rotation::None,
+            sheet,
+            anims,
+            curr: 0,
+            frame: 0,
+            ticks: 0,
+            done: false,
+        }
+    }
+
+    /// Switch animation. Does nothing if it is already playing, use `restart` to start over.
+    /// Returns false if there is no animation with that name.
+    pub fn play(&mut self, name: &str) -> bool {
+        let Some(idx) = self.anims.iter().position(|anim| anim.name == name) else {
+            return false;
+        };
+
+        if idx != self.curr {
+            self.curr = idx;
+            self.restart();
+        }
+        true
+    }
+
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.ticks = 0;
+        self.done = false;
+    }
+
+    pub fn tick(&mut self) {
+        if self.done {
+            return;
+        }
+
+        let anim = &self.anims[self.curr];
+        self.ticks += 1;
+        if self.ticks < anim.frames[self.frame].ticks {
+            return;
+        }
+
+        self.ticks = 0;
+        if self.frame + 1 < anim.frames.len() {
+            self.frame += 1;
+        } else if anim.playback == Playback::Loop {
+            self.frame = 0;
+        } else {
+            self.done = true;
+        }
+    }
+
+    /// A `Once` animation has shown its last frame for its full duration.
+    pub fn finished(&self) -> bool {
+        self.done
+    }
+
+    pub fn name(&self) -> &str {
+        self.anims[self.curr].name
+    }
+
+    /// Index into the sheet of the frame being shown.
+    pub fn sheet_frame(&self) -> usize {
+        self.anims[self.curr].frames[self.frame].idx as usize
+    }
+
+    pub fn draw<D>(&self, pos: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        self.sheet
+            .draw_frame(self.sheet_frame(), pos, self.flip, self.rotation, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    /// Four 2x1 frames in a row.
+    const SHEET: SpriteSheet = SpriteSheet::new(
+        Sprite::new(8, 1, 0, 0, &[0b1001_0110]),
+        Frames::Grid(Size::new(2, 1)),
+    );
+    const ANIMS: &[Animation] = &[
+        Animation::new(
+            "walk",
+            &[Frame::new(0, 2), Frame::new(1, 1), Frame::new(2, 3)],
+            Playback::Loop,
+        ),
+        Animation::new("die", &[Frame::new(3, 2), Frame::new(0, 1)], Playback::Once),
+    ];
+
+    /// Sheet frames shown over `ticks` updates.
+    fn shown(animator: &mut Animator, ticks: usize) -> Vec<usize> {
+        (0..ticks)
+            .map(|_| {
+                let frame = animator.sheet_frame();
+                animator.tick();
+                frame
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loops_with_frame_durations() {
+        let mut animator = Animator::new(&SHEET, ANIMS);
+        assert_eq!(animator.name(), "walk");
+        assert_eq!(shown(&mut animator, 9), [0, 0, 1, 2, 2, 2, 0, 0, 1]);
+        assert!(!animator.finished());
+    }
+
+    #[test]
+    fn once_stops_on_the_last_frame() {
+        let mut animator = Animator::new(&SHEET, ANIMS);
+        assert!(animator.play("die"));
+        assert_eq!(shown(&mut animator, 2), [3, 3]);
+        assert!(!animator.finished());
+        assert_eq!(shown(&mut animator, 4), [0, 0, 0, 0]);
+        assert!(animator.finished());
+
+        animator.restart();
+        assert!(!animator.finished());
+        assert_eq!(animator.sheet_frame(), 3);
+    }
+
+    #[test]
+    fn play() {
+        let mut animator = Animator::new(&SHEET, ANIMS);
+        shown(&mut animator, 3);
+        assert!(!animator.play("fly"));
+        assert_eq!(animator.name(), "walk");
+        assert_eq!(animator.sheet_frame(), 2);
+
+        // Already playing, carries on
+        assert!(animator.play("walk"));
+        assert_eq!(animator.sheet_frame(), 2);
+
+        assert!(animator.play("die"));
+        assert!(animator.play("walk"));
+        assert_eq!(animator.sheet_frame(), 0);
+    }
+
+    #[test]
+    fn sheet_frames() {
+        assert_eq!(SHEET.frame_count(), 4);
+        assert_eq!(
+            SHEET.frame(3),
+            Rectangle::new(Point::new(6, 0), Size::new(2, 1))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no animations")]
+    fn no_animations() {
+        Animator::new(&SHEET, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "no frames")]
+    fn no_frames() {
+        let anims = [Animation {
+            name: "empty",
+            frames: &[],
+            playback: Playback::Loop,
+        }];
+        Animator::new(&SHEET, &anims);
+    }
+
+    #[test]
+    #[should_panic(expected = "sheet doesn't have")]
+    fn frame_off_the_sheet() {
+        const OFF: &[Animation] = &[Animation::new("off", &[Frame::new(4, 1)], Playback::Loop)];
+        Animator::new(&SHEET, OFF);
+    }
+}
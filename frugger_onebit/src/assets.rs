@@ -0,0 +1,10 @@
+//! Sprites generated from `assets/` by the build script.
+
+// Not every build has sheets in it
+#[allow(unused_imports)]
+use crate::anim::{Frames, SpriteSheet};
+use crate::util::Sprite;
+#[allow(unused_imports)]
+use embedded_graphics::geometry::Size;
+
+include!(concat!(env!("OUT_DIR"), "/assets.rs"));
@@ -0,0 +1,44 @@
+use crate::transition::BitFrame;
+use core::convert::Infallible;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// Retained layer of a `OneBit`, from `OneBit::background`. Whatever is drawn here shows up in
+/// this frame and every frame after, under the normal per frame drawing, until it is drawn over
+/// or cleared.
+pub struct Background<'a> {
+    pub(crate) layer: &'a mut BitFrame,
+    pub(crate) next_frame: &'a mut [BinaryColor; 8192],
+    pub(crate) bounds: Rectangle,
+    /// Shake this frame, only for `next_frame`.
+    pub(crate) shift: Point,
+}
+
+impl Dimensions for Background<'_> {
+    fn bounding_box(&self) -> Rectangle {
+        self.bounds
+    }
+}
+
+impl DrawTarget for Background<'_> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let width = self.bounds.size.width as i32;
+        for Pixel(point, col) in pixels {
+            if self.bounds.contains(point) {
+                self.layer.set((point.y * width + point.x) as usize, col);
+            }
+            let shown = point + self.shift;
+            if self.bounds.contains(shown) {
+                self.next_frame[(shown.y * width + shown.x) as usize] = col;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// Viewport into a larger world. Games keep objects in world coordinates and draw them through
+/// the camera instead of shifting everything when the screen scrolls.
+#[derive(Copy, Clone)]
+pub struct Camera {
+    /// World position of the top left of the screen.
+    pub pos: Point,
+    size: Size,
+}
+
+impl Camera {
+    pub fn new(size: Size) -> Self {
+        Self {
+            pos: Point::zero(),
+            size,
+        }
+    }
+
+    /// Camera covering the whole of `target`.
+    pub fn for_target<D: Dimensions>(target: &D) -> Self {
+        Self::new(target.bounding_box().size)
+    }
+
+    pub fn view(&self) -> Rectangle {
+        Rectangle::new(self.pos, self.size)
+    }
+
+    pub fn to_screen(&self, world: Point) -> Point {
+        world - self.pos
+    }
+
+    pub fn to_world(&self, screen: Point) -> Point {
+        screen + self.pos
+    }
+
+    /// Add to world coordinates to get screen coordinates, for use with `DrawTargetExt::translated`.
+    pub fn offset(&self) -> Point {
+        -self.pos
+    }
+
+    /// False if `bounds` (in world coordinates) is entirely off screen and can be skipped.
+    pub fn visible(&self, bounds: &Rectangle) -> bool {
+        !self.view().intersection(bounds).is_zero_sized()
+    }
+
+    /// Centre the view on `target`.
+    pub fn center_on(&mut self, target: Point) {
+        self.pos = target - Point::new(self.size.width as i32 / 2, self.size.height as i32 / 2);
+    }
+
+    /// Camera for a background layer that moves `num / den` as fast as this one.
+    pub fn parallax(&self, num: i32, den: i32) -> Self {
+        Self {
+            pos: self.pos * num / den,
+            size: self.size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible() {
+        let mut camera = Camera::new(Size::new(64, 128));
+        camera.pos = Point::new(0, -200);
+        let at = |x, y| Rectangle::new(Point::new(x, y), Size::new(10, 3));
+
+        assert!(camera.visible(&at(20, -150)));
+        // One pixel in at each edge, then just outside
+        assert!(camera.visible(&at(-9, -150)));
+        assert!(camera.visible(&at(63, -150)));
+        assert!(camera.visible(&at(20, -202)));
+        assert!(camera.visible(&at(20, -73)));
+        assert!(!camera.visible(&at(-10, -150)));
+        assert!(!camera.visible(&at(64, -150)));
+        assert!(!camera.visible(&at(20, -203)));
+        assert!(!camera.visible(&at(20, -72)));
+    }
+
+    #[test]
+    fn screen_and_world() {
+        let mut camera = Camera::new(Size::new(64, 128));
+        camera.center_on(Point::new(100, 100));
+        assert_eq!(camera.pos, Point::new(68, 36));
+        assert_eq!(camera.to_screen(Point::new(100, 100)), Point::new(32, 64));
+        assert_eq!(camera.to_world(Point::new(32, 64)), Point::new(100, 100));
+        assert_eq!(Point::new(100, 100) + camera.offset(), Point::new(32, 64));
+    }
+
+    #[test]
+    fn parallax() {
+        let mut camera = Camera::new(Size::new(64, 128));
+        camera.pos = Point::new(40, -100);
+        let far = camera.parallax(1, 4);
+        assert_eq!(far.pos, Point::new(10, -25));
+        assert_eq!(far.view().size, camera.view().size);
+        assert_eq!(camera.parallax(1, 1).pos, camera.pos);
+        assert_eq!(camera.parallax(0, 1).pos, Point::zero());
+    }
+}
@@ -0,0 +1,190 @@
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// Number of gray levels, 0 is all Off and `LEVELS` all On.
+pub const LEVELS: u8 = 16;
+
+/// 4x4 Bayer matrix, a pixel is On if its threshold is under the gray level.
+pub const BAYER_4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Ordered dither, whether the pixel at `x`, `y` is On at gray `level` (0 to `LEVELS`).
+pub fn dither(x: i32, y: i32, level: u8) -> bool {
+    BAYER_4[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize] < level
+}
+
+/// Gray for a fraction of the way from Off to On, clamped to 0..=1.
+pub fn level(fraction: f32) -> u8 {
+    (fraction.clamp(0.0, 1.0) * LEVELS as f32 + 0.5) as u8
+}
+
+/// Fill patterns, aligned to the coordinates they are drawn at so neighbouring shapes line up.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Pattern {
+    /// Ordered dither gray, 0 to `LEVELS`.
+    Gray(u8),
+    Checker,
+    /// Horizontal lines every n pixels.
+    HLines(u8),
+    /// Vertical lines every n pixels.
+    VLines(u8),
+    /// Lines going down to the right every n pixels.
+    Diagonal(u8),
+    /// One dot in each n by n cell.
+    Dots(u8),
+}
+
+impl Pattern {
+    pub fn color_at(&self, p: Point) -> BinaryColor {
+        let on = match *self {
+            Pattern::Gray(level) => dither(p.x, p.y, level),
+            Pattern::Checker => (p.x + p.y).rem_euclid(2) == 0,
+            Pattern::HLines(n) => p.y.rem_euclid(n.max(1) as i32) == 0,
+            Pattern::VLines(n) => p.x.rem_euclid(n.max(1) as i32) == 0,
+            Pattern::Diagonal(n) => (p.x - p.y).rem_euclid(n.max(1) as i32) == 0,
+            Pattern::Dots(n) => {
+                let n = n.max(1) as i32;
+                p.x.rem_euclid(n) == 0 && p.y.rem_euclid(n) == 0
+            }
+        };
+        on.into()
+    }
+}
+
+/// Draws On pixels as the pattern and skips Off ones, so any embedded-graphics primitive filled
+/// or stroked with On comes out patterned. The pattern's Off pixels are drawn too, set
+/// `Blend::Or` on the engine to keep what is underneath instead.
+pub struct Patterned<'a, D> {
+    target: &'a mut D,
+    pattern: Pattern,
+}
+
+impl<D> Dimensions for Patterned<'_, D>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D> DrawTarget for Patterned<'_, D>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    type Color = BinaryColor;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let pattern = self.pattern;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .filter(|Pixel(_, col)| col.is_on())
+                .map(|Pixel(p, _)| Pixel(p, pattern.color_at(p))),
+        )
+    }
+}
+
+pub trait PatternExt: DrawTarget<Color = BinaryColor> + Sized {
+    /// Draw through `pattern`, see `Patterned`.
+    fn patterned(&mut self, pattern: Pattern) -> Patterned<'_, Self>;
+}
+
+impl<D: DrawTarget<Color = BinaryColor>> PatternExt for D {
+    fn patterned(&mut self, pattern: Pattern) -> Patterned<'_, Self> {
+        Patterned {
+            target: self,
+            pattern,
+        }
+    }
+}
+
+/// Fill `area` with gray `level`.
+pub fn fill_gray<D>(target: &mut D, area: &Rectangle, level: u8) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    target.fill_contiguous(
+        area,
+        area.points().map(|p| Pattern::Gray(level).color_at(p)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frugger_core::mock::Recorder;
+
+    #[test]
+    fn dither_levels() {
+        // Any 4x4 window holds each threshold once, aligned or not
+        for level in [0, 1, 8, 15, LEVELS] {
+            for corner in [Point::zero(), Point::new(-4, -8), Point::new(5, -3)] {
+                let lit = Rectangle::new(corner, Size::new(4, 4))
+                    .points()
+                    .filter(|p| dither(p.x, p.y, level))
+                    .count();
+                assert_eq!(lit, level as usize, "{level} at {corner:?}");
+            }
+        }
+        assert_eq!(level(-1.0), 0);
+        assert_eq!(level(0.5), 8);
+        assert_eq!(level(2.0), LEVELS);
+    }
+
+    fn on(pattern: Pattern, x: i32, y: i32) -> bool {
+        pattern.color_at(Point::new(x, y)).is_on()
+    }
+
+    #[test]
+    fn negative_coordinates() {
+        // Carry on across 0 without a seam
+        assert!(on(Pattern::Checker, -1, -1) && !on(Pattern::Checker, -1, 0));
+        assert!(on(Pattern::HLines(3), 5, -3) && !on(Pattern::HLines(3), 5, -1));
+        assert!(on(Pattern::VLines(3), -3, 5) && !on(Pattern::VLines(3), -2, 5));
+        assert!(on(Pattern::Diagonal(3), -1, -1) && on(Pattern::Diagonal(3), -3, 0));
+        assert!(!on(Pattern::Diagonal(3), -2, 0));
+        assert!(on(Pattern::Dots(3), -3, -6) && !on(Pattern::Dots(3), -3, -2));
+        for (x, y) in [(-1, -1), (-4, 3), (-7, -2)] {
+            let gray = Pattern::Gray(8);
+            assert_eq!(on(gray, x, y), on(gray, x + 4, y + 8));
+        }
+    }
+
+    #[test]
+    fn zero_spacing() {
+        let grid = Rectangle::new(Point::new(-3, -3), Size::new(6, 6));
+        for (zero, one) in [
+            (Pattern::HLines(0), Pattern::HLines(1)),
+            (Pattern::VLines(0), Pattern::VLines(1)),
+            (Pattern::Diagonal(0), Pattern::Diagonal(1)),
+            (Pattern::Dots(0), Pattern::Dots(1)),
+        ] {
+            // Every pixel, as if it were 1
+            assert!(grid.points().all(|p| zero.color_at(p).is_on()));
+            assert!(grid.points().all(|p| zero.color_at(p) == one.color_at(p)));
+        }
+        assert!(grid.points().all(|p| !Pattern::Gray(0).color_at(p).is_on()));
+    }
+
+    #[test]
+    fn drops_off_pixels() {
+        let mut target = Recorder::new(Size::new(4, 1), BinaryColor::On);
+        let pixels = [
+            Pixel(Point::new(0, 0), BinaryColor::Off),
+            Pixel(Point::new(1, 0), BinaryColor::On),
+            Pixel(Point::new(2, 0), BinaryColor::On),
+        ];
+        target
+            .patterned(Pattern::Checker)
+            .draw_iter(pixels)
+            .unwrap();
+        assert_eq!(target.pushed(), 2);
+        let row: [bool; 4] = core::array::from_fn(|x| target.pixels()[x].is_on());
+        assert_eq!(row, [true, false, true, true]);
+    }
+}
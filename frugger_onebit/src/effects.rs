@@ -0,0 +1,70 @@
+use embedded_graphics::prelude::Point;
+
+/// Random jitter that dies off linearly over its frames.
+pub(crate) struct Shake {
+    amplitude: u8,
+    frames: u16,
+    remaining: u16,
+    seed: u32,
+}
+
+impl Shake {
+    /// Shakes from different `seed`s follow different paths.
+    pub(crate) fn new(amplitude: u8, frames: u16, seed: u32) -> Self {
+        Self {
+            amplitude,
+            frames,
+            remaining: frames,
+            // xorshift sticks at 0
+            seed: (seed ^ 0x9E37_79B9).max(1),
+        }
+    }
+
+    /// Offset for the next frame, `None` once it has died off.
+    pub(crate) fn tick(&mut self) -> Option<Point> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let amp = (self.amplitude as u32 * self.remaining as u32).div_ceil(self.frames as u32);
+        let range = amp * 2 + 1;
+        let x = (self.next() % range) as i32 - amp as i32;
+        let y = (self.next() % range) as i32 - amp as i32;
+        Some(Point::new(x, y))
+    }
+
+    /// xorshift, no need for a real rng here.
+    fn next(&mut self) -> u32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    fn path(seed: u32) -> Vec<Point> {
+        let mut shake = Shake::new(3, 30, seed);
+        core::iter::from_fn(|| shake.tick()).collect()
+    }
+
+    #[test]
+    fn seeds() {
+        assert_eq!(path(7), path(7));
+        assert_ne!(path(7), path(8));
+        // The one that xors to 0
+        assert_eq!(path(0x9E37_79B9).len(), 30);
+    }
+
+    #[test]
+    fn dies_off() {
+        let path = path(1);
+        assert!(path.iter().all(|p| p.x.abs() <= 3 && p.y.abs() <= 3));
+        assert_eq!(path.last(), Some(&Point::zero()));
+    }
+}
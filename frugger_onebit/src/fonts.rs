@@ -0,0 +1,243 @@
+//! Fonts built from `assets/fonts/`, see the build script. `FONT_TINY` fits 16 characters across
+//! the portrait screen, `FONT_DIGITS` is for big scores.
+
+use embedded_graphics::image::ImageRaw;
+use embedded_graphics::mono_font::mapping::StrGlyphMapping;
+use embedded_graphics::mono_font::{DecorationDimensions, MonoFont};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::renderer::{TextMetrics, TextRenderer};
+use embedded_graphics::text::Baseline;
+
+include!(concat!(env!("OUT_DIR"), "/fonts.rs"));
+
+/// Font where each glyph only takes up as much room as it needs.
+pub struct PropFont<'a> {
+    /// Packed glyph sheet, 16 glyphs to a row.
+    data: &'a [u8],
+    width: u32,
+    cell: Size,
+    glyphs: &'a str,
+    widths: &'a [u32],
+    baseline: u32,
+}
+
+impl<'a> PropFont<'a> {
+    pub const fn new(
+        data: &'a [u8],
+        width: u32,
+        cell: Size,
+        glyphs: &'a str,
+        widths: &'a [u32],
+        baseline: u32,
+    ) -> Self {
+        Self {
+            data,
+            width,
+            cell,
+            glyphs,
+            widths,
+            baseline,
+        }
+    }
+
+    /// Unknown characters fall back to `?`, or nothing if there isn't one.
+    fn glyph(&self, c: char) -> Option<usize> {
+        self.glyphs
+            .chars()
+            .position(|g| g == c)
+            .or_else(|| self.glyphs.chars().position(|g| g == '?'))
+    }
+
+    fn advance(&self, c: char) -> u32 {
+        self.glyph(c).map_or(0, |idx| self.widths[idx] + 1)
+    }
+
+    /// Width of `text` in pixels.
+    pub fn text_width(&self, text: &str) -> u32 {
+        text.chars()
+            .map(|c| self.advance(c))
+            .sum::<u32>()
+            .saturating_sub(1)
+    }
+
+    fn lit(&self, idx: usize, x: u32, y: u32) -> bool {
+        let cols = (self.width / self.cell.width) as usize;
+        let px = (idx % cols) as u32 * self.cell.width + x;
+        let py = (idx / cols) as u32 * self.cell.height + y;
+        let byte = (py * self.width.div_ceil(8) + px / 8) as usize;
+        self.data[byte] & (0x80 >> (px % 8)) != 0
+    }
+}
+
+/// Text style for a `PropFont`, use it with `Text` like a `MonoTextStyle`.
+#[derive(Copy, Clone)]
+pub struct PropTextStyle<'a> {
+    pub font: &'a PropFont<'a>,
+    pub color: BinaryColor,
+}
+
+impl<'a> PropTextStyle<'a> {
+    pub const fn new(font: &'a PropFont<'a>, color: BinaryColor) -> Self {
+        Self { font, color }
+    }
+
+    fn top(&self, position: Point, baseline: Baseline) -> Point {
+        let offset = match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => self.font.cell.height - 1,
+            Baseline::Middle => (self.font.cell.height - 1) / 2,
+            Baseline::Alphabetic => self.font.baseline,
+        };
+        position - Point::new(0, offset as i32)
+    }
+}
+
+impl TextRenderer for PropTextStyle<'_> {
+    type Color = BinaryColor;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let top = self.top(position, baseline);
+        let mut x = 0;
+        for c in text.chars() {
+            let Some(idx) = self.font.glyph(c) else {
+                continue;
+            };
+            let width = self.font.widths[idx];
+            let origin = top + Point::new(x as i32, 0);
+            target.draw_iter(
+                (0..self.font.cell.height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .filter(|(gx, gy)| self.font.lit(idx, *gx, *gy))
+                    .map(|(gx, gy)| Pixel(origin + Point::new(gx as i32, gy as i32), self.color)),
+            )?;
+            x += width + 1;
+        }
+        Ok(position + Point::new(x.saturating_sub(1) as i32, 0))
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        _baseline: Baseline,
+        _target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        Ok(position + Point::new(width as i32, 0))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let size = Size::new(self.font.text_width(text), self.font.cell.height);
+        TextMetrics {
+            bounding_box: Rectangle::new(self.top(position, baseline), size),
+            next_position: position + size.x_axis(),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.cell.height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::text::Text;
+    use frugger_core::mock::Recorder;
+    use std::vec::Vec;
+
+    const STYLE: PropTextStyle = PropTextStyle::new(&FONT_PROP, BinaryColor::On);
+
+    /// Lit pixels of `text` drawn from the top left at `x`, as rows of `#`.
+    fn render(text: &str, x: i32) -> Vec<std::string::String> {
+        let mut target = Recorder::new(Size::new(24, 5), BinaryColor::Off);
+        Text::with_baseline(text, Point::new(x, 0), STYLE, Baseline::Top)
+            .draw(&mut target)
+            .unwrap();
+        target
+            .pixels()
+            .chunks(24)
+            .map(|row| {
+                row.iter()
+                    .map(|c| if c.is_on() { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(FONT_PROP.text_width(""), 0);
+        assert_eq!(FONT_PROP.text_width("I"), 1);
+        assert_eq!(FONT_PROP.text_width("A"), 3);
+        assert_eq!(FONT_PROP.text_width("M"), 5);
+        // A pixel between glyphs, spaces are half a cell
+        assert_eq!(FONT_PROP.text_width("AIM"), 11);
+        assert_eq!(FONT_PROP.text_width("I I"), 7);
+        // Unknown is drawn as '?'
+        assert_eq!(FONT_PROP.text_width("\u{e9}"), FONT_PROP.text_width("?"));
+    }
+
+    #[test]
+    fn glyphs() {
+        #[rustfmt::skip]
+        let expected = [
+            ".#..#.#...#.##..........",
+            "#.#.#.##.##...#.........",
+            "###.#.#.#.#..#..........",
+            "#.#.#.#...#.............",
+            "#.#.#.#...#..#..........",
+        ];
+        assert_eq!(render("AIM\u{e9}", 0), expected);
+        // Moved along, nothing cut off
+        assert_eq!(render("AIM", 3)[1], "...#.#.#.##.##..........");
+    }
+
+    #[test]
+    fn metrics() {
+        let metrics = STYLE.measure_string("AIM", Point::new(2, 10), Baseline::Alphabetic);
+        assert_eq!(
+            metrics.bounding_box,
+            Rectangle::new(Point::new(2, 6), Size::new(11, 5))
+        );
+        assert_eq!(metrics.next_position, Point::new(13, 10));
+
+        let mut target = Recorder::new(Size::new(24, 5), BinaryColor::Off);
+        let next = STYLE
+            .draw_string("AIM", Point::new(2, 2), Baseline::Middle, &mut target)
+            .unwrap();
+        assert_eq!(next, metrics.next_position - Point::new(0, 8));
+    }
+
+    #[test]
+    fn no_fallback() {
+        // Without a '?' unknown characters take no room
+        let font = PropFont::new(&[0xC0, 0xC0], 8, Size::new(4, 2), "AB", &[2, 3], 1);
+        assert_eq!(font.text_width("AXB"), 6);
+        assert_eq!(font.text_width("X"), 0);
+        let mut target = Recorder::new(Size::new(8, 2), BinaryColor::Off);
+        Text::with_baseline(
+            "XA",
+            Point::zero(),
+            PropTextStyle::new(&font, BinaryColor::On),
+            Baseline::Top,
+        )
+        .draw(&mut target)
+        .unwrap();
+        assert_eq!(target.pixels().iter().filter(|c| c.is_on()).count(), 4);
+        assert!(target.get(Point::new(1, 1)).unwrap().is_on());
+    }
+}
@@ -0,0 +1,66 @@
+use crate::fonts::{FONT_DIGITS, FONT_SMALL, FONT_TINY};
+use crate::widgets::{Label, List};
+use crate::{OneBit, Signal};
+use embedded_graphics::mono_font::ascii::{FONT_6X10, FONT_8X13};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use frugger_core::num_text::{NumText, Pad};
+use frugger_core::FrugInputs;
+
+/// End of a game: the final and best score, then retry or back to the menu. The choice goes to
+/// the menu as a signal on the game's engine.
+pub struct GameOver {
+    score: u16,
+    /// Best before this game.
+    best: u16,
+    options: List<'static>,
+}
+
+impl GameOver {
+    const OPTIONS: [&'static str; 2] = ["RETRY", "MENU"];
+    const LIST: Rectangle = Rectangle::new(Point::new(8, 90), Size::new(48, 24));
+
+    pub fn new(score: u16, best: u16) -> Self {
+        Self {
+            score,
+            best,
+            options: List::new(&Self::OPTIONS, &FONT_6X10),
+        }
+    }
+
+    pub fn update(&mut self, inputs: &FrugInputs, engine: &mut OneBit) {
+        Label::new("GAME\nOVER", &FONT_8X13)
+            .draw(&Rectangle::new(Point::new(0, 6), Size::new(64, 13)), engine)
+            .unwrap();
+
+        Label::new(NumText::num(self.score as i32).as_str(), &FONT_DIGITS)
+            .draw(
+                &Rectangle::new(Point::new(0, 42), Size::new(64, 12)),
+                engine,
+            )
+            .unwrap();
+        Label::new("SCORE", &FONT_TINY)
+            .draw(&Rectangle::new(Point::new(0, 57), Size::new(64, 5)), engine)
+            .unwrap();
+
+        let mut best = NumText::new();
+        if self.score > self.best {
+            best.push_str("NEW BEST!");
+        } else {
+            best.push_str("BEST ")
+                .push_num(self.best as i32, 0, Pad::Spaces);
+        }
+        Label::new(best.as_str(), &FONT_SMALL)
+            .draw(&Rectangle::new(Point::new(0, 70), Size::new(64, 6)), engine)
+            .unwrap();
+
+        if let Some(choice) = self.options.update(inputs) {
+            engine.signal = Some(if choice == 0 {
+                Signal::Retry
+            } else {
+                Signal::Quit
+            });
+        }
+        self.options.draw(&Self::LIST, engine).unwrap();
+    }
+}
@@ -1,14 +1,15 @@
 use crate::util::SM;
+use crate::widgets::Label;
 use crate::{OneBit, Signal};
 use embedded_graphics::mono_font::ascii::FONT_8X13;
-use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::{PrimitiveStyle, Rectangle, StyledDrawable};
-use embedded_graphics::text::{Alignment, Text};
+use frugger_core::num_text::NumText;
+use frugger_core::tween::{Ease, Tween};
 use frugger_core::{FrugInputs, FruggerGame, Orientation};
 use heapless::Vec;
-use numtoa::NumToA;
+use libm::roundf;
 use rand::prelude::SmallRng;
 use rand::{Rng, SeedableRng};
 use crate::menu::SaveOffset;
@@ -16,10 +17,15 @@ use crate::menu::SaveOffset;
 struct State {
     rng: SmallRng,
     tiles: [Point; 3],
+    /// Filling it wins.
     sequence: Vec<u8, 100>,
     ptr: usize,
+    /// Rounds got right, the score.
+    passed: u16,
     timer: u64,
     showing: bool,
+    /// Slides the PASS and LOSER text in.
+    pop: Tween,
 }
 
 pub struct MatchMe {
@@ -32,6 +38,8 @@ impl MatchMe {
     const RECT: Rectangle = Rectangle::new(Point::zero(), Size::new_equal(16));
     const PRESSED: PrimitiveStyle<BinaryColor> = PrimitiveStyle::with_stroke(BinaryColor::On, 4);
     const OFF: PrimitiveStyle<BinaryColor> = PrimitiveStyle::with_stroke(BinaryColor::On, 1);
+    /// Line the messages are centred on, more lines go below.
+    const TEXT: Rectangle = Rectangle::new(Point::new(0, 22), Size::new(64, 13));
 
     fn draw_blank(tiles: &[Point; 3], engine: &mut OneBit) {
         for tile in tiles {
@@ -120,10 +128,13 @@ impl MatchMe {
                 }
 
                 if state.sequence.len() == state.ptr {
-                    // Correct! add to sequence, start again
-                    state.sequence.push(state.rng.gen_range(0..=2));
+                    // Correct! add to sequence, start again. Nothing left to add is a win
+                    state.passed += 1;
                     state.ptr = 0;
-                    return 4;
+                    return match state.sequence.push(state.rng.gen_range(0..=2)) {
+                        Ok(()) => 4,
+                        Err(_) => 5,
+                    };
                 }
 
                 return 2;
@@ -156,10 +167,12 @@ impl MatchMe {
                 MatchMe::draw_tile(&state.tiles[1], inputs.a.down(), engine);
                 MatchMe::draw_tile(&state.tiles[2], inputs.right.down(), engine);
 
-                Self::draw_text("PASS", engine);
                 if state.timer == 0 {
                     state.timer = 60;
+                    state.pop = Tween::new(0.0, 32.0, 30, Ease::BounceOut);
                 }
+                let y = roundf(state.pop.tick()) as i32;
+                Self::draw_text_at("PASS", Point::new(0, y - 32), engine);
 
                 state.timer -= 1;
 
@@ -171,19 +184,26 @@ impl MatchMe {
             },
         );
 
-        // loser
+        // loser, or winner with a full sequence
         sm.add(
             5,
             |state: &mut State, inputs: &FrugInputs, engine: &mut OneBit| {
-                Self::draw_text("LOSER", engine);
                 if state.timer == 0 {
                     state.timer = 60;
+                    state.pop = Tween::new(-32.0, 32.0, 40, Ease::ElasticOut);
                 }
+                let x = roundf(state.pop.tick()) as i32;
+                let won = usize::from(state.passed) == state.sequence.capacity();
+                let text = if won { "WINNER" } else { "LOSER" };
+                Self::draw_text_at(text, Point::new(x - 32, 0), engine);
 
                 state.timer -= 1;
 
                 if state.timer == 0 {
-                    engine.signal = Some(Signal::Save {score: state.sequence.len() as u16 - 1, save_offset: SaveOffset::MatchScores});
+                    engine.signal = Some(Signal::GameOver {
+                        score: state.passed,
+                        save_offset: SaveOffset::MatchScores,
+                    });
                     return 5;
                 }
                 5
@@ -201,8 +221,10 @@ impl MatchMe {
                 tiles: [Point::new(4, 50), Point::new(24, 70), Point::new(44, 50)],
                 sequence,
                 ptr: 0,
+                passed: 0,
                 timer: 2 * MatchMe::TARGET_FPS,
                 showing: true,
+                pop: Tween::idle(0.0),
             },
             sm,
         }
@@ -216,18 +238,16 @@ impl MatchMe {
     }
 
     fn draw_step(step: usize, engine: &mut OneBit) {
-        let mut buf = [0u8; 20];
-        let step_str = ((step + 1) as u16).numtoa_str(10, &mut buf);
-        Self::draw_text(step_str, engine);
+        Self::draw_text(NumText::num(step as i32 + 1).as_str(), engine);
     }
     fn draw_text(content: &str, engine: &mut OneBit) {
-        let mut text = Text::new(
-            content,
-            Point::new(32, 32),
-            MonoTextStyle::new(&FONT_8X13, BinaryColor::On),
-        );
-        text.text_style.alignment = Alignment::Center;
-        text.draw(engine);
+        Self::draw_text_at(content, Point::zero(), engine);
+    }
+    /// `offset` from where the text normally sits.
+    fn draw_text_at(content: &str, offset: Point, engine: &mut OneBit) {
+        Label::new(content, &FONT_8X13)
+            .draw(&Self::TEXT.translate(offset), engine)
+            .unwrap();
     }
 }
 
@@ -245,3 +265,57 @@ impl FruggerGame for MatchMe {
         &mut self.engine
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frugger_core::ButtonState;
+
+    /// Plays until the game ends, answering right until `rounds` are passed then wrong. Returns
+    /// the score.
+    fn play(game: &mut MatchMe, rounds: u16) -> u16 {
+        for frame in 0..100_000 {
+            let mut inputs = FrugInputs::default();
+            // Tap every other frame, only heard on the player's turn
+            if frame % 2 == 0 {
+                let want = game.state.sequence[game.state.ptr];
+                let button = if game.state.passed < rounds {
+                    want
+                } else {
+                    (want + 1) % 3
+                };
+                *[&mut inputs.left, &mut inputs.a, &mut inputs.right][button as usize] =
+                    ButtonState::PRESSED;
+            }
+            game.update(&inputs);
+
+            if let Some(Signal::GameOver { score, .. }) = game.engine.signal.take() {
+                return score;
+            }
+        }
+        panic!("never ended");
+    }
+
+    #[test]
+    fn lose_straight_away() {
+        assert_eq!(play(&mut MatchMe::new(1), 0), 0);
+    }
+
+    #[test]
+    fn lose_later() {
+        assert_eq!(play(&mut MatchMe::new(1), 2), 2);
+    }
+
+    #[test]
+    fn win() {
+        // Straight to the last round
+        let mut game = MatchMe::new(1);
+        while !game.state.sequence.is_full() {
+            let next = game.state.sequence.len() as u8 % 3;
+            game.state.sequence.push(next).unwrap();
+        }
+        game.state.passed = 99;
+        assert_eq!(play(&mut game, 100), 100);
+        assert_eq!(game.state.sequence.len(), 100);
+    }
+}
@@ -1,11 +1,20 @@
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
-use embedded_graphics::primitives::{Line, PrimitiveStyle, StyledDrawable, Triangle};
+use embedded_graphics::primitives::{Line, PrimitiveStyle, Rectangle, StyledDrawable};
+use frugger_core::collision::{Segment, Vec2};
+use frugger_core::num_text::Pad;
 use frugger_core::{FrugInputs, FruggerGame, Orientation};
 use libm::roundf;
 use rand::prelude::SmallRng;
 use rand::{Rng, SeedableRng};
-use crate::OneBit;
+use crate::anim::{Animation, Animator, Frame, Playback};
+use crate::assets;
+use crate::camera::Camera;
+use crate::fonts::FONT_TINY;
+use crate::hud::{Format, Readout};
+use crate::particles::{Emission, Particles};
+use crate::menu::SaveOffset;
+use crate::{OneBit, Signal};
 
 #[derive(Clone, Default)]
 struct Pos(f32, f32);
@@ -14,13 +23,26 @@ impl Pos {
     fn point(&self) -> Point {
         Point::new(roundf(self.0) as _, roundf(self.1) as _)
     }
+
+    fn vec(&self) -> Vec2 {
+        Vec2::new(self.0, self.1)
+    }
 }
 struct State {
     walls: heapless::Vec<((Pos, Pos), (Pos, Pos)), 20>,
     player_pos: Pos,
     player_vel: f32,
+    car: Animator<'static>,
     rng: SmallRng,
-    road_min: f32, // score: u32,
+    road_min: f32,
+    /// Distance driven.
+    score: u32,
+    score_hud: Readout<'static>,
+    camera: Camera,
+    particles: Particles<48>,
+    crashed: bool,
+    /// Frames since the crash, the game ends when the explosion has settled.
+    wrecked: u16,
 }
 
 pub struct Racer {
@@ -40,24 +62,42 @@ impl Racer {
                 .unwrap(),
                 player_pos: Pos(32.0, 115.0),
                 player_vel: 0.0,
+                car: Animator::new(&assets::CAR, Self::CAR_ANIMS),
                 rng: SmallRng::seed_from_u64(rng),
                 road_min: 20.0,
+                score: 0,
+                score_hud: Self::SCORE_HUD,
+                camera: Camera::new(Size::new(64, 128)),
+                particles: Particles::new(rng),
+                crashed: false,
+                wrecked: 0,
             },
         }
     }
 
+    const SCORE_HUD: Readout<'static> = Readout::new(
+        "",
+        Format::Number { width: 0, pad: Pad::Spaces },
+        &FONT_TINY,
+        Rectangle::new(Point::new(1, 1), Size::new(62, 5)),
+    );
     const WALL_STYLE: PrimitiveStyle<BinaryColor> =
         PrimitiveStyle::with_stroke(BinaryColor::On, 2);
-    const PLAYER_STYLE: PrimitiveStyle<BinaryColor> =
-        PrimitiveStyle::with_stroke(BinaryColor::On, 1);
+    const WRECK_FRAMES: u16 = 90;
     const LEFT: f32 = -1.2;
     const RIGHT: f32 = 1.2;
 
-    const PLAYER: Triangle = Triangle::new(Point::new(0, 0), Point::new(-4, 10), Point::new(4, 10));
-    const PLAYER_L: Triangle =
-        Triangle::new(Point::new(-1, 0), Point::new(-3, 11), Point::new(5, 9));
-    const PLAYER_R: Triangle =
-        Triangle::new(Point::new(1, 0), Point::new(-5, 9), Point::new(3, 11));
+    const CAR_ANIMS: &'static [Animation<'static>] = &[
+        Animation::new(
+            "drive",
+            &[Frame::new(0, 4), Frame::new(1, 4)],
+            Playback::Loop,
+        ),
+        Animation::new("left", &[Frame::new(2, 1)], Playback::Loop),
+        Animation::new("right", &[Frame::new(3, 1)], Playback::Loop),
+    ];
+    /// From the car's nose to the top left of its frame.
+    const CAR_OFFSET: Point = Point::new(-5, 0);
 }
 
 impl FruggerGame for Racer {
@@ -68,68 +108,90 @@ impl FruggerGame for Racer {
 
     fn update(&mut self, inputs: &FrugInputs) {
         // Inputs
-        let sprite = if inputs.left.down() {
+        let anim = if self.state.crashed {
+            "drive"
+        } else if inputs.left.down() {
             self.state.player_pos.0 += Self::LEFT;
-            Self::PLAYER_L
+            "left"
         } else if inputs.right.down() {
             self.state.player_pos.0 += Self::RIGHT;
-            Self::PLAYER_R
+            "right"
         } else {
-            Self::PLAYER
+            "drive"
         };
+        self.state.car.play(anim);
+        self.state.car.tick();
 
         // Collision check
         let ((l1, l2), (r1, r2)) = &self.state.walls[0];
-        let player_x = self.state.player_pos.0;
-        let v1 = (l2.0 - l1.0, l2.1 - l1.1);
-        let v2 = (player_x - l1.0, 122.0 - l1.1);
-        let cp= v1.0 * v2.1 - v1.1 * v2.0;
-
-        let v1 = (r2.0 - r1.0, r2.1 - r1.1);
-        let v2 = (player_x - r1.0, 122.0 - r1.1);
-        let cp2= v1.0 * v2.1 - v1.1 * v2.0;
-        if cp < 0.0 || cp2 > 0.0 {
-            // TODO gameover
-            return;
+        let player = Vec2::new(
+            self.state.player_pos.0,
+            self.state.camera.pos.y as f32 + 122.0,
+        );
+        let left = Segment::new(l1.vec(), l2.vec());
+        let right = Segment::new(r1.vec(), r2.vec());
+        if !self.state.crashed && (left.side(player) < 0.0 || right.side(player) > 0.0) {
+            self.state.crashed = true;
+            self.engine.shake(4, 45);
+            self.engine.flash(3);
+            let crash_at = self.state.player_pos.point() + Point::new(0, 5);
+            self.state
+                .particles
+                .burst(crash_at, 40, &Emission::EXPLOSION);
         }
 
+        if self.state.crashed {
+            self.state.wrecked += 1;
+            if self.state.wrecked == Self::WRECK_FRAMES {
+                self.engine.signal = Some(Signal::GameOver {
+                    score: self.state.score.min(u16::MAX as u32) as u16,
+                    save_offset: SaveOffset::RacerScores,
+                });
+            }
+        }
 
-        // Shift everything to move the screen up
-        let move_amt = 2.0;
-        if move_amt > 0.0 {
-            self.state.walls.iter_mut().for_each(
-                |((Pos(_, y), Pos(_, y2)), (Pos(_, y3), Pos(_, y4)))| {
-                    *y += move_amt;
-                    *y2 += move_amt;
-                    *y3 += move_amt;
-                    *y4 += move_amt;
-                },
-            );
+        // Move the camera and the player up the road
+        if !self.state.crashed {
+            let move_amt = 2;
+            self.state.camera.pos.y -= move_amt;
+            self.state.player_pos.1 -= move_amt as f32;
+            self.state.score += move_amt as u32;
         }
+        self.state.particles.update();
+
+        // Drawn first, it lives in the background
+        self.state.score_hud.set(self.state.score as i32);
+        self.state.score_hud.draw(&mut self.engine);
+
+        let camera = self.state.camera;
+        let mut view = self.engine.translated(camera.offset());
 
         // Draw walls
         self.state.walls.iter().for_each(|(w1, w2)| {
-            Line::new(w1.0.point(), w1.1.point())
-                .draw_styled(&Self::WALL_STYLE, &mut self.engine)
-                .unwrap();
-            Line::new(w2.0.point(), w2.1.point())
-                .draw_styled(&Self::WALL_STYLE, &mut self.engine)
-                .unwrap();
+            for wall in [
+                Line::new(w1.0.point(), w1.1.point()),
+                Line::new(w2.0.point(), w2.1.point()),
+            ] {
+                if camera.visible(&wall.bounding_box()) {
+                    wall.draw_styled(&Self::WALL_STYLE, &mut view).unwrap();
+                }
+            }
         });
 
-        // Draw player
-        sprite
-            .translate(self.state.player_pos.point())
-            .draw_styled(&Self::PLAYER_STYLE, &mut self.engine)
-            .unwrap();
-
+        // Draw player, replaced by the explosion once crashed
+        if !self.state.crashed {
+            self.state
+                .car
+                .draw(self.state.player_pos.point() + Self::CAR_OFFSET, &mut view)
+                .unwrap();
+        }
+        self.state.particles.draw(&mut view).unwrap();
 
 
-        // Draw score
-        // Text::with_text_style(self.state.score.to_string().as_str(), Point::zero(), )
 
         // Clean old walls and spawn new ones
-        self.state.walls.retain(|(w1, _)| w1.1 .1 < 129.0);
+        let bottom = camera.pos.y as f32 + 129.0;
+        self.state.walls.retain(|(w1, _)| w1.1 .1 < bottom);
 
         while self.state.walls.len() < 15 {
             let (last_left, last_right) = self.state.walls.last().unwrap();
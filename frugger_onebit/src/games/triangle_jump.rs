@@ -1,7 +1,15 @@
+use crate::assets;
+use crate::camera::Camera;
+use crate::fonts::FONT_TINY;
+use crate::hud::{Format, Readout};
+use crate::particles::{Emission, Particles};
+use crate::tilemap::Tilemap;
 use crate::{OneBit, Signal};
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::{PrimitiveStyle, Rectangle, StyledDrawable, Triangle};
+use frugger_core::collision::{Aabb, Vec2};
+use frugger_core::num_text::Pad;
 use frugger_core::{FrugInputs, FruggerGame, Orientation};
 use libm::roundf;
 use rand::prelude::SmallRng;
@@ -15,6 +23,10 @@ impl Pos {
     fn point(&self) -> Point {
         Point::new(roundf(self.0) as _, roundf(self.1) as _)
     }
+
+    fn vec(&self) -> Vec2 {
+        Vec2::new(self.0, self.1)
+    }
 }
 struct State {
     platforms: heapless::Vec<Pos, 100>,
@@ -22,6 +34,12 @@ struct State {
     player_vel: f32,
     rng: SmallRng,
     score: u32,
+    score_hud: Readout<'static>,
+    camera: Camera,
+    /// Camera height before rounding.
+    cam_y: f32,
+    /// Landing dust.
+    particles: Particles<32>,
 }
 
 pub struct Jump {
@@ -44,10 +62,20 @@ impl Jump {
                 player_vel: 0.0,
                 rng: SmallRng::seed_from_u64(rng),
                 score: 0,
+                score_hud: Self::SCORE_HUD,
+                camera: Camera::new(Size::new(64, 128)),
+                cam_y: 0.0,
+                particles: Particles::new(rng),
             },
         }
     }
 
+    const SCORE_HUD: Readout<'static> = Readout::new(
+        "",
+        Format::Number { width: 0, pad: Pad::Spaces },
+        &FONT_TINY,
+        Rectangle::new(Point::new(1, 1), Size::new(62, 5)),
+    );
     const PLAYER_STYLE: PrimitiveStyle<BinaryColor> =
         PrimitiveStyle::with_stroke(BinaryColor::On, 1);
     const LEFT: f32 = -1.0;
@@ -63,6 +91,27 @@ impl Jump {
     const PLAYER: Triangle = Triangle::new(Point::new(0, 0), Point::new(-3, 10), Point::new(3, 10));
 
     const MAX_DIST: f32 = 30.0;
+
+    /// Far behind the platforms, repeats every screen.
+    #[rustfmt::skip]
+    const STARS: Tilemap<'static> = Tilemap::new(&assets::STARS, 8, &[
+        0, 0, 0, 1, 0, 0, 0, 0,
+        0, 3, 0, 0, 0, 0, 2, 0,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 1, 0, 0, 0,
+        2, 0, 0, 0, 0, 0, 0, 1,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 1, 0, 0, 3, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 1, 0,
+        0, 1, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 2, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        3, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 1, 0, 0, 0, 2,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 1, 0, 0, 0, 0, 0,
+    ]).repeating();
 }
 
 impl FruggerGame for Jump {
@@ -85,64 +134,81 @@ impl FruggerGame for Jump {
 
         // Check if on top of a platform
         let player = &self.state.player_pos;
+        // Platforms exactly half a width to the left don't count, just like the right
+        let feet = Aabb::new(
+            Vec2::new((player.0 - Jump::PLAT_HALF_W).next_up(), player.1),
+            Vec2::new(player.0 + Jump::PLAT_HALF_W, player.1 + 2.0),
+        );
 
         if self.state.player_vel > 0.0 {
-            for Pos(x, y) in &self.state.platforms {
-                if (*y >= player.1 && *y < player.1 + 2.0)
-                    && (*x < player.0 + Jump::PLAT_HALF_W && *x > player.0 - Jump::PLAT_HALF_W)
-                {
+            for platform in &self.state.platforms {
+                if feet.contains(platform.vec()) {
                     self.state.player_vel = -2.0;
+                    let landed_at = Point::new(roundf(player.0) as _, roundf(platform.1) as _);
+                    self.state.particles.burst(landed_at, 6, &Emission::DUST);
                     break;
                 }
             }
         }
 
+        self.state.particles.update();
+
         // Gravity - always applies
         self.state.player_vel += Self::GRAVITY;
 
         // Apply velocity
         self.state.player_pos.1 += self.state.player_vel;
 
-        // Shift everything to move the screen up
-        let move_amt = 64.0 - self.state.player_pos.1;
+        // Move the camera up to follow the player
+        let move_amt = 64.0 - (self.state.player_pos.1 - self.state.cam_y);
         if move_amt > 0.0 {
-            self.state
-                .platforms
-                .iter_mut()
-                .for_each(|Pos(_, y)| *y += move_amt);
-            self.state.player_pos.1 += move_amt;
+            self.state.cam_y -= move_amt;
             self.state.score += move_amt as u32;
         }
+        self.state.camera.pos.y = roundf(self.state.cam_y) as i32;
 
         // dead check
-        if self.state.player_pos.1 > 500.0 {
-            self.engine.signal = Some(Signal::Save {
+        if self.state.player_pos.1 - self.state.cam_y > 500.0 {
+            self.engine.signal = Some(Signal::GameOver {
                 score: self.state.score as u16,
                 save_offset: SaveOffset::TriangleScores,
             })
         }
 
-        // Draw platforms
+        // Drawn first, it lives in the background
+        self.state.score_hud.set(self.state.score as i32);
+        self.state.score_hud.draw(&mut self.engine);
+
+        let camera = self.state.camera;
+        Self::STARS
+            .draw(&camera.parallax(1, 4), &mut self.engine)
+            .unwrap();
+        let mut view = self.engine.translated(camera.offset());
+
+        // Draw platforms, skipping the ones spawned above the screen
         self.state.platforms.iter().for_each(|platform| {
-            Jump::PLATFORM
+            let platform = Jump::PLATFORM
                 .translate(platform.point())
-                .translate_mut(Point::new(-5, 0))
-                .draw_styled(&Self::PLAYER_STYLE, &mut self.engine)
-                .unwrap();
+                .translate(Point::new(-5, 0));
+            if camera.visible(&platform) {
+                platform
+                    .draw_styled(&Self::PLAYER_STYLE, &mut view)
+                    .unwrap();
+            }
         });
 
         // Draw player
         Jump::PLAYER
             .translate(self.state.player_pos.point())
             .translate_mut(Point::new(0, -10))
-            .draw_styled(&Self::PLAYER_STYLE, &mut self.engine)
+            .draw_styled(&Self::PLAYER_STYLE, &mut view)
             .unwrap();
 
-        // Draw score
-        // Text::with_text_style(self.state.score.to_string().as_str(), Point::zero(), )
+        self.state.particles.draw(&mut view).unwrap();
 
         // Clean up oob platforms and spawn new ones
-        self.state.platforms.retain(|platform| platform.1 < 129.0);
+        let bottom = self.state.cam_y + 129.0;
+        self.state.platforms.retain(|platform| platform.1 < bottom);
 
         while self.state.platforms.len() < 10 {
             let last = self.state.platforms.last().unwrap();
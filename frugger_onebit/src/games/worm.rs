@@ -1,18 +1,16 @@
-use crate::OneBit;
-use embedded_graphics::mono_font::ascii::FONT_8X13;
-use embedded_graphics::mono_font::MonoTextStyle;
+use crate::menu::SaveOffset;
+use crate::{OneBit, Signal};
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::Point;
 use embedded_graphics::primitives::{Circle, PrimitiveStyle, StyledDrawable};
-use embedded_graphics::text::{Alignment, Text};
-use embedded_graphics::Drawable;
+use frugger_core::collision::{Circle as Hitbox, Vec2};
 use frugger_core::{FrugInputs, FruggerGame, Orientation};
 use heapless::Deque;
-use libm::{cosf, roundf, sinf, sqrt};
+use libm::{cosf, roundf, sinf};
 use rand::prelude::SmallRng;
 use rand::{Rng, SeedableRng};
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 struct Pos(f32, f32);
 
 impl Pos {
@@ -23,10 +21,13 @@ impl Pos {
 
 struct GameState {
     apple: Circle,
+    /// Head first. Once it is full the tail goes as the head grows, the worm stops getting longer.
     segments: Deque<Pos, 1000>,
     dir: f32,
     speed: f32,
     rng: SmallRng,
+    /// Apples eaten.
+    score: u16,
     game_over: bool,
 }
 
@@ -48,6 +49,7 @@ impl SmolWorm {
                 dir: 0.0,
                 speed: 0.5,
                 rng: SmallRng::seed_from_u64(rng),
+                score: 0,
                 game_over: false,
             },
         };
@@ -72,34 +74,16 @@ impl SmolWorm {
         new_head.0 = (new_head.0 + 64.0) % 64.0;
         new_head.1 = (new_head.1 + 128.0) % 128.0;
 
-        self.state.segments.push_front(new_head.clone());
+        if self.state.segments.is_full() {
+            self.state.segments.pop_back();
+        }
+        self.state.segments.push_front(new_head.clone()).unwrap();
         new_head
     }
-    fn draw_text(content: &str, engine: &mut OneBit) {
-        let mut text = Text::new(
-            content,
-            Point::new(32, 32),
-            MonoTextStyle::new(&FONT_8X13, BinaryColor::On),
-        );
-        text.text_style.alignment = Alignment::Center;
-        text.draw(engine);
-    }
-
     const APPLE_STYLE: PrimitiveStyle<BinaryColor> = PrimitiveStyle::with_fill(BinaryColor::On);
     const WORM_STYLE: PrimitiveStyle<BinaryColor> = PrimitiveStyle::with_fill(BinaryColor::On);
 }
 
-trait Distance {
-    fn distance(&self, other: &Point) -> f32;
-}
-impl Distance for Point {
-    fn distance(&self, other: &Point) -> f32 {
-        let dx = (self.x - other.x).pow(2);
-        let dy = (self.y - other.y).pow(2);
-        sqrt((dx + dy) as f64) as f32
-    }
-}
-
 impl FruggerGame for SmolWorm {
     const TARGET_FPS: u64 = 60;
     const ORIENTATION: Orientation = Orientation::Portrait;
@@ -119,17 +103,21 @@ impl FruggerGame for SmolWorm {
         let head = if !self.state.game_over {
             &self.add_head()
         } else {
-            if self.state.segments.len() == 0 {
-                Self::draw_text("GAME\nOVER", &mut self.engine);
+            // The worm has shrunk away, hand over to the game over screen
+            if self.state.segments.is_empty() {
+                self.engine.signal = Some(Signal::GameOver {
+                    score: self.state.score,
+                    save_offset: SaveOffset::WormScores,
+                });
                 return;
             }
 
             self.state.segments.front().unwrap()
         };
-        let head = head.point();
+        let head = Vec2::from(head.point());
 
         // Pad the collision box a bit
-        if self.state.apple.center().distance(&head) < 3.5 {
+        if Hitbox::new(self.state.apple.center().into(), 3.5).contains(head) {
             // Don't remove the tail, move the apple
             self.add_head();
             self.add_head();
@@ -138,6 +126,7 @@ impl FruggerGame for SmolWorm {
                 self.state.rng.gen_range(2..126),
             );
             self.state.speed += 0.035;
+            self.state.score += 1;
         } else {
             // Remove the tail so we stay the same length
             self.state.segments.pop_back();
@@ -147,7 +136,11 @@ impl FruggerGame for SmolWorm {
             let point = seg.point();
 
             // Check for collisions
-            if i > 10 && point.distance(&head) <= 2.0 {
+            if i > 10 && Hitbox::new(point.into(), 2.0).contains(head) {
+                if !self.state.game_over {
+                    self.engine.shake(3, 40);
+                    self.engine.flash(4);
+                }
                 self.state.game_over = true;
             }
             Circle::with_center(point, 2)
@@ -165,3 +158,32 @@ impl FruggerGame for SmolWorm {
         &mut self.engine
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_worm() {
+        let mut worm = SmolWorm::new(1);
+        // Out of the way so it doesn't run into itself
+        while !worm.state.segments.is_full() {
+            worm.state.segments.push_back(Pos(10.0, 10.0)).unwrap();
+        }
+        worm.state.segments.back_mut().unwrap().0 = 11.0;
+
+        // Eating grows it by two
+        let head = worm.state.segments.front().unwrap().point();
+        worm.state.apple = Circle::with_center(head, 4);
+        worm.update(&FrugInputs::default());
+        assert_eq!(worm.state.score, 1);
+        assert!(!worm.state.game_over);
+        assert_eq!(worm.state.segments.len(), 1000);
+        assert_eq!(worm.state.segments.back().unwrap().0, 10.0);
+
+        // Not eating takes the tail off as well
+        worm.state.apple.top_left = Point::new(50, 120);
+        worm.update(&FrugInputs::default());
+        assert_eq!(worm.state.segments.len(), 999);
+    }
+}
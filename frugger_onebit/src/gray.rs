@@ -0,0 +1,73 @@
+use crate::transition::BitFrame;
+use core::convert::Infallible;
+use embedded_graphics::pixelcolor::{Gray2, GrayColor};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// 2 bit pixels kept as two planes. The high plane is shown for two flushes and the low plane
+/// for one, so a pixel is lit for `luma` out of every 3 and the panel's persistence does the rest.
+pub(crate) struct GrayPlanes {
+    hi: BitFrame,
+    lo: BitFrame,
+}
+
+impl GrayPlanes {
+    pub(crate) const SUBFRAMES: u8 = 3;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            hi: BitFrame::new(),
+            lo: BitFrame::new(),
+        }
+    }
+
+    pub(crate) fn set(&mut self, idx: usize, col: Gray2) {
+        let luma = col.luma();
+        self.hi.set(idx, (luma & 0b10 != 0).into());
+        self.lo.set(idx, (luma & 0b01 != 0).into());
+    }
+
+    /// Whether the pixel is lit on flush `subframe` of `SUBFRAMES`.
+    pub(crate) fn on(&self, idx: usize, subframe: u8) -> bool {
+        match subframe {
+            0 | 1 => self.hi.get(idx).is_on(),
+            _ => self.lo.get(idx).is_on(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.hi = BitFrame::new();
+        self.lo = BitFrame::new();
+    }
+}
+
+/// Gray layer of a `OneBit` in gray mode, under anything drawn On in the normal layer. From
+/// `OneBit::gray`.
+pub struct GrayLayer<'a> {
+    pub(crate) planes: &'a mut GrayPlanes,
+    pub(crate) bounds: Rectangle,
+}
+
+impl Dimensions for GrayLayer<'_> {
+    fn bounding_box(&self) -> Rectangle {
+        self.bounds
+    }
+}
+
+impl DrawTarget for GrayLayer<'_> {
+    type Color = Gray2;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let width = self.bounds.size.width as i32;
+        for Pixel(point, col) in pixels {
+            if self.bounds.contains(point) {
+                self.planes.set((point.y * width + point.x) as usize, col);
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,89 +1,94 @@
 use crate::ui::RESET_BUTTONS;
+use crate::widgets::{Label, TextEntry};
 use crate::OneBit;
-use core::fmt::Write;
-use embedded_graphics::mono_font::ascii::{FONT_5X8, FONT_6X12, FONT_8X13};
-use embedded_graphics::mono_font::MonoTextStyle;
-use embedded_graphics::pixelcolor::BinaryColor;
+use crate::fonts::{FONT_DIGITS, FONT_SMALL, FONT_TINY};
+use embedded_graphics::mono_font::ascii::FONT_6X12;
 use embedded_graphics::prelude::*;
-use embedded_graphics::text::{Alignment, Text};
-use frugger_core::{FrugInputs, FruggerGame, Orientation};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::Alignment;
+use frugger_core::num_text::NumText;
+use frugger_core::FrugInputs;
 use heapless::{String, Vec};
 
 struct State {
     score_table: Vec<(String<3>, u16), 5>,
     new_score: u16,
     new_score_line: usize,
-    new_name: [u8; 3],
-    curr_idx: usize,
-    frame: usize,
+    /// Best before the new score.
+    best: u16,
+    /// Name for the new score, until it is entered.
+    entry: Option<TextEntry<'static, 3>>,
+    save_offset: usize,
     save_fn: fn(usize, [u8; 32]),
 }
 
 pub struct HiScore {
-    engine: OneBit,
     state: State,
 }
 
-impl FruggerGame for HiScore {
-    const TARGET_FPS: u64 = 60;
-    const ORIENTATION: Orientation = Orientation::Portrait;
-    type Color = BinaryColor;
-    type Engine = OneBit;
-
-    fn update(&mut self, inputs: &FrugInputs) {
-        self.state.frame += 1;
-
+impl HiScore {
+    /// True once the name is entered and A is pressed to move on.
+    pub fn update(&mut self, inputs: &FrugInputs, engine: &mut OneBit) -> bool {
         let state = &mut self.state;
-        let engine = &mut self.engine;
 
         // No high score, not interactive.
         // Render scores
-        Self::draw_header("HI\nSCORES", engine);
+        Self::draw_header("HI SCORES", engine);
         Self::draw_scores(state, engine);
         Self::draw_edit(state, engine);
         Self::draw_your_score(state, engine);
         RESET_BUTTONS.draw(engine);
 
-        // Nothing to do
-        if state.new_score_line == 10 {
-            return;
-        }
+        // No new score or already entered
+        let Some(entry) = &mut state.entry else {
+            Label::new("PRESS A", &FONT_TINY)
+                .draw(&Rectangle::new(Point::new(0, 103), Size::new(64, 5)), engine)
+                .unwrap();
+            return inputs.a.pressed();
+        };
 
-        // exit early, already done
-        if state.curr_idx == 3 {
-            return;
+        if entry.update(inputs) {
+            // Save
+            let name = &mut state.score_table[state.new_score_line].0;
+            name.clear();
+            name.push_str(entry.text()).unwrap();
+            state.entry = None;
+            (state.save_fn)(state.save_offset, score_to_data(&state.score_table));
         }
+        false
+    }
 
-        if inputs.left.pressed() {
-            let curr = state.new_name[state.curr_idx] + 26;
-            state.new_name[state.curr_idx] = (curr - 1) % 26;
-        }
-        if inputs.right.pressed() {
-            let curr = state.new_name[state.curr_idx];
-            state.new_name[state.curr_idx] = (curr + 1) % 26;
-        }
-        if inputs.a.pressed() {
-            state.curr_idx += 1;
-
-            if state.curr_idx == 3 {
-                // Save
-                let name = &mut state.score_table[state.new_score_line].0;
-                name.clear();
-                name.push((state.new_name[0] + 65) as char);
-                name.push((state.new_name[1] + 65) as char);
-                name.push((state.new_name[2] + 65) as char);
-                (state.save_fn)(0, score_to_data(&state.score_table));
-            }
-        }
+    pub fn score(&self) -> u16 {
+        self.state.new_score
     }
 
-    fn frugger(&mut self) -> &mut Self::Engine {
-        &mut self.engine
+    /// Best score before this one.
+    pub fn best(&self) -> u16 {
+        self.state.best
     }
-}
 
-impl HiScore {
-    pub fn new(loaded: &[u8], new_score: u16, save_fn: fn(usize, [u8; 32])) -> Self {
+    /// Whether `score` makes it into the table saved in `loaded`.
+    pub fn qualifies(loaded: &[u8], score: u16) -> bool {
+        Self::placing(&Self::load_table(loaded), score) != 10
+    }
+
+    /// Top score of the table saved in `loaded`.
+    pub fn best_in(loaded: &[u8]) -> u16 {
+        Self::load_table(loaded)
+            .iter()
+            .map(|(_, score)| *score)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether `loaded` looks like a saved table, every record flag is set or clear.
+    pub fn valid(loaded: &[u8]) -> bool {
+        loaded[..30]
+            .chunks(6)
+            .all(|record| record[0] == 0 || record[0] == u8::MAX)
+    }
+
+    fn load_table(loaded: &[u8]) -> Vec<(String<3>, u16), 5> {
         let mut score_table = Vec::new();
 
         // Each record is 6 bytes (bool, entry exists) + 3 bytes (name) + 2 bytes (score)
@@ -93,17 +98,25 @@ impl HiScore {
             let end = start + 6;
             let score_slice = &loaded[start..end];
 
+            // Erased storage reads all 0xFF, which can't be saved as no name has '@' in it
+            if score_slice[0] != u8::MAX || score_slice.iter().all(|byte| *byte == u8::MAX) {
+                continue;
+            }
 
             let mut name_str = String::<3>::new();
-            name_str.push((score_slice[1] + 65) as char).unwrap();
-            name_str.push((score_slice[2] + 65) as char).unwrap();
-            name_str.push((score_slice[3] + 65) as char).unwrap();
+            for byte in &score_slice[1..4] {
+                let c = byte.wrapping_add(65);
+                name_str.push(if c.is_ascii() { c as char } else { '?' }).unwrap();
+            }
 
             let score = u16::from_le_bytes(score_slice[4..6].try_into().unwrap());
-            score_table.push((name_str, score));
+            score_table.push((name_str, score)).unwrap();
         }
+        score_table
+    }
 
-        // Figure out where our new score fits in.
+    /// Line `new_score` goes on, 10 if it doesn't make the table.
+    fn placing(score_table: &Vec<(String<3>, u16), 5>, new_score: u16) -> usize {
         let mut new_score_line = 10;
 
         for (i, (_, score)) in score_table.iter().enumerate() {
@@ -116,6 +129,19 @@ impl HiScore {
         if new_score_line == 10 && score_table.len() < 5 {
             new_score_line = score_table.len();
         }
+        new_score_line
+    }
+
+    /// `save_offset` is where `loaded` came from, it is passed back to `save_fn`.
+    pub fn new(
+        loaded: &[u8],
+        new_score: u16,
+        save_offset: usize,
+        save_fn: fn(usize, [u8; 32]),
+    ) -> Self {
+        let mut score_table = Self::load_table(loaded);
+        let best = Self::best_in(loaded);
+        let new_score_line = Self::placing(&score_table, new_score);
 
         // Insert it
         if new_score_line != 10 {
@@ -127,88 +153,58 @@ impl HiScore {
         }
 
         Self {
-            engine: OneBit::new(Self::ORIENTATION),
             state: State {
                 score_table,
                 new_score,
                 new_score_line,
-                new_name: [0, 0, 0],
-                curr_idx: 0,
-                frame: 0,
+                best,
+                entry: (new_score_line != 10).then(|| TextEntry::new(&FONT_6X12)),
+                save_offset,
                 save_fn,
             },
         }
     }
-    fn draw_edit(state: &State, engine: &mut OneBit) {
-        if state.new_score_line == 10 {
-            return;
-        }
-
-        let mut tmp = String::<3>::new();
+    /// Strip for a line of the table, the name goes on the left and the score on the right.
+    fn row(line: usize) -> Rectangle {
+        Rectangle::new(Point::new(3, 41 + line as i32 * 10), Size::new(59, 12))
+    }
 
-        for (i, char) in state.new_name.iter().enumerate() {
-            if i == state.curr_idx && state.frame % 30 > 15 {
-                tmp.push(' ').unwrap();
-            } else {
-                tmp.push((char + 65) as char).unwrap();
-            }
+    fn draw_edit(state: &State, engine: &mut OneBit) {
+        if let Some(entry) = &state.entry {
+            entry
+                .draw(&Self::row(state.new_score_line), engine)
+                .unwrap();
         }
-
-        let mut name_text = Text::new(
-            tmp.as_str(),
-            Point::new(3, (50 + (state.new_score_line * 10)) as _),
-            MonoTextStyle::new(&FONT_6X12, BinaryColor::On),
-        );
-        name_text.text_style.alignment = Alignment::Left;
-        name_text.draw(engine);
     }
     fn draw_scores(state: &State, engine: &mut OneBit) {
         for (line, (name, score)) in state.score_table.iter().enumerate() {
-            // if state.new_score_line == line {
-            //     continue;
-            // }
-            let mut name_text = Text::new(
-                name,
-                Point::new(3, (50 + (line * 10)) as _),
-                MonoTextStyle::new(&FONT_6X12, BinaryColor::On),
-            );
-            name_text.text_style.alignment = Alignment::Left;
-            name_text.draw(engine);
-
-            let mut score_text = heapless::String::<11>::new();
-            write!(&mut score_text, "{}", score).unwrap();
-
-            let mut score_text = Text::new(
-                &score_text,
-                Point::new(64 - 3, (50 + (line * 10)) as _),
-                MonoTextStyle::new(&FONT_6X12, BinaryColor::On),
-            );
-            score_text.text_style.alignment = Alignment::Right;
-            score_text.draw(engine);
+            let row = Self::row(line);
+            Label::new(name, &FONT_6X12)
+                .align(Alignment::Left)
+                .draw(&row, engine)
+                .unwrap();
+
+            Label::new(NumText::num(*score as i32).as_str(), &FONT_6X12)
+                .align(Alignment::Right)
+                .draw(&row, engine)
+                .unwrap();
         }
     }
 
     fn draw_your_score(state: &State, engine: &mut OneBit) {
-        let mut score_text = String::<20>::new();
-        write!(&mut score_text, "SCORE: {}", state.new_score).unwrap();
+        Label::new(NumText::num(state.new_score as i32).as_str(), &FONT_DIGITS)
+            .draw(&Rectangle::new(Point::new(0, 20), Size::new(64, 12)), engine)
+            .unwrap();
 
-        let mut score_text = Text::new(
-            &score_text,
-            Point::new(32, 106),
-            MonoTextStyle::new(&FONT_5X8, BinaryColor::On),
-        );
-        score_text.text_style.alignment = Alignment::Center;
-        score_text.draw(engine);
+        Label::new("YOUR SCORE", &FONT_TINY)
+            .draw(&Rectangle::new(Point::new(0, 35), Size::new(64, 5)), engine)
+            .unwrap();
     }
 
     fn draw_header(content: &str, engine: &mut OneBit) {
-        let mut text = Text::new(
-            content,
-            Point::new(32, 20),
-            MonoTextStyle::new(&FONT_8X13, BinaryColor::On),
-        );
-        text.text_style.alignment = Alignment::Center;
-        text.draw(engine);
+        Label::new(content, &FONT_SMALL)
+            .draw(&Rectangle::new(Point::new(0, 5), Size::new(64, 6)), engine)
+            .unwrap();
     }
 }
 
@@ -218,13 +214,74 @@ fn score_to_data(scores: &Vec<(String<3>, u16), 5>) -> [u8; 32] {
     for (i, (name, score)) in scores.iter().enumerate() {
         let offset = i * 6;
         buf[offset] = u8::MAX;
-        let name = name.as_bytes();
-        buf[offset + 1] = name[0] - 65;
-        buf[offset + 2] = name[1] - 65;
-        buf[offset + 3] = name[2] - 65;
+        // Stored relative to 'A', shorter names are padded with spaces
+        for (j, byte) in name.bytes().chain([b' '; 3]).take(3).enumerate() {
+            buf[offset + 1 + j] = byte.wrapping_sub(65);
+        }
         buf[offset + 4] = score.to_le_bytes()[0];
         buf[offset + 5] = score.to_le_bytes()[1];
     }
 
     buf
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frugger_core::{ButtonState, Orientation};
+    use std::cell::Cell;
+
+    std::thread_local! {
+        static SAVED: Cell<Option<(usize, [u8; 32])>> = const { Cell::new(None) };
+    }
+
+    fn save(offset: usize, data: [u8; 32]) {
+        SAVED.set(Some((offset, data)));
+    }
+
+    fn pressed(button: fn(&mut FrugInputs) -> &mut ButtonState) -> FrugInputs {
+        let mut inputs = FrugInputs::default();
+        *button(&mut inputs) = ButtonState::PRESSED;
+        inputs
+    }
+
+    #[test]
+    fn never_written() {
+        for byte in [0x00, 0xFF] {
+            let loaded = [byte; 32];
+            assert!(HiScore::valid(&loaded));
+            assert!(HiScore::load_table(&loaded).is_empty(), "{byte:#x}");
+            assert_eq!(HiScore::best_in(&loaded), 0);
+            assert!(HiScore::qualifies(&loaded, 1));
+
+            // Enter "A" and save it
+            let mut engine = OneBit::new(Orientation::Portrait);
+            let mut scores = HiScore::new(&loaded, 250, 3, save);
+            assert_eq!(scores.best(), 0);
+            let script: [fn(&mut FrugInputs) -> &mut ButtonState; 3] =
+                [|i| &mut i.a, |i| &mut i.left, |i| &mut i.a];
+            for button in script {
+                assert!(!scores.update(&pressed(button), &mut engine));
+            }
+            let (offset, data) = SAVED.take().unwrap();
+            assert_eq!(offset, 3);
+            let table = HiScore::load_table(&data);
+            assert_eq!(table.len(), 1);
+            assert_eq!(table[0], (String::try_from("A  ").unwrap(), 250));
+        }
+    }
+
+    #[test]
+    fn skips_unset_records() {
+        let table: Vec<_, 5> = [("ABC", 30), ("XYZ", 20)]
+            .into_iter()
+            .map(|(name, score)| (String::try_from(name).unwrap(), score))
+            .collect();
+        let mut loaded = score_to_data(&table);
+        assert_eq!(HiScore::load_table(&loaded), table);
+
+        loaded[0] = 0;
+        assert_eq!(HiScore::load_table(&loaded)[..], table[1..]);
+        assert_eq!(HiScore::best_in(&loaded), 20);
+    }
+}
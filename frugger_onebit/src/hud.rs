@@ -0,0 +1,207 @@
+use crate::widgets::Label;
+use crate::OneBit;
+use embedded_graphics::mono_font::MonoFont;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::Alignment;
+use frugger_core::num_text::{NumText, Pad};
+
+/// How a `Readout` shows its value.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    /// Score, level and the like, at least `width` characters wide.
+    Number { width: u8, pad: Pad },
+    /// A `*` for each life.
+    Lives,
+    /// Seconds as `M:SS`.
+    Time,
+}
+
+/// One HUD value with a label, like `LV 3`. Drawn into the engine's background so it only
+/// redraws when the value changes, draw the game after it.
+pub struct Readout<'a> {
+    label: &'a str,
+    format: Format,
+    font: &'a MonoFont<'a>,
+    area: Rectangle,
+    align: Alignment,
+    value: i32,
+    /// What is in the background now.
+    shown: Option<i32>,
+}
+
+impl<'a> Readout<'a> {
+    /// Left aligned in `area`.
+    pub const fn new(
+        label: &'a str,
+        format: Format,
+        font: &'a MonoFont<'a>,
+        area: Rectangle,
+    ) -> Self {
+        Self {
+            label,
+            format,
+            font,
+            area,
+            align: Alignment::Left,
+            value: 0,
+            shown: None,
+        }
+    }
+
+    pub const fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn set(&mut self, value: i32) {
+        self.value = value;
+    }
+
+    /// Draw again next time, for after the background was cleared.
+    pub fn invalidate(&mut self) {
+        self.shown = None;
+    }
+
+    pub fn text(&self) -> NumText {
+        let mut text = NumText::new();
+        text.push_str(self.label);
+        match self.format {
+            Format::Number { width, pad } => {
+                text.push_num(self.value, width as usize, pad);
+            }
+            Format::Lives => {
+                text.push_repeat(b'*', self.value.max(0) as usize);
+            }
+            Format::Time => {
+                let time = NumText::time(self.value.max(0) as u32);
+                text.push_str(time.as_str());
+            }
+        }
+        text
+    }
+
+    pub fn draw(&mut self, engine: &mut OneBit) {
+        if self.shown == Some(self.value) {
+            return;
+        }
+
+        let mut background = engine.background();
+        background.fill_solid(&self.area, BinaryColor::Off).unwrap();
+        Label::new(self.text().as_str(), self.font)
+            .align(self.align)
+            .draw(&self.area, &mut background)
+            .unwrap();
+        self.shown = Some(self.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fonts::FONT_TINY;
+    use frugger_core::Orientation;
+    use std::vec::Vec;
+
+    const AREA: Rectangle = Rectangle::new(Point::new(4, 2), Size::new(40, 5));
+
+    fn readout(format: Format) -> Readout<'static> {
+        Readout::new("LV", format, &FONT_TINY, AREA)
+    }
+
+    /// Points lit in the background after drawing `readout` with `value`.
+    fn drawn(readout: &mut Readout, value: i32, engine: &mut OneBit) -> Vec<Point> {
+        readout.set(value);
+        readout.draw(engine);
+        let layer = engine.background.as_ref().unwrap();
+        (0..64 * 128)
+            .filter(|idx| layer.get(*idx).is_on())
+            .map(|idx| Point::new(idx as i32 % 64, idx as i32 / 64))
+            .collect()
+    }
+
+    #[test]
+    fn formats() {
+        let number = |width, pad, value| {
+            let mut readout = readout(Format::Number { width, pad });
+            readout.set(value);
+            readout.text()
+        };
+        assert_eq!(number(4, Pad::Zeros, 42).as_str(), "LV0042");
+        assert_eq!(number(4, Pad::Zeros, -42).as_str(), "LV-042");
+        assert_eq!(number(4, Pad::Spaces, 42).as_str(), "LV  42");
+        assert_eq!(number(0, Pad::Spaces, 12345).as_str(), "LV12345");
+
+        let mut lives = readout(Format::Lives);
+        lives.set(3);
+        assert_eq!(lives.text().as_str(), "LV***");
+        lives.set(-1);
+        assert_eq!(lives.text().as_str(), "LV");
+
+        let mut time = readout(Format::Time);
+        time.set(75);
+        assert_eq!(time.text().as_str(), "LV1:15");
+        time.set(-5);
+        assert_eq!(time.text().as_str(), "LV0:00");
+    }
+
+    #[test]
+    fn alignment() {
+        let format = Format::Number {
+            width: 2,
+            pad: Pad::Zeros,
+        };
+        // "LV07" is 4 glyphs 3 wide with a pixel between, 15 across
+        let right = AREA.top_left.x + AREA.size.width as i32 - 1;
+        for (align, first) in [
+            (Alignment::Left, AREA.top_left.x),
+            (Alignment::Right, right - 14),
+        ] {
+            let mut engine = OneBit::new(Orientation::Portrait);
+            let lit = drawn(&mut readout(format).align(align), 7, &mut engine);
+            assert!(lit.iter().all(|p| AREA.contains(*p)));
+            let xs = lit.iter().map(|p| p.x);
+            assert_eq!(xs.clone().min(), Some(first));
+            assert_eq!(xs.max(), Some(first + 14));
+        }
+    }
+
+    #[test]
+    fn only_redraws_changes() {
+        let mut engine = OneBit::new(Orientation::Portrait);
+        let mut readout = readout(Format::Lives);
+        let first = drawn(&mut readout, 3, &mut engine);
+
+        // Drawing over the same value would wipe this
+        let marker = Rectangle::new(AREA.top_left + Point::new(30, 0), Size::new(1, 1));
+        engine
+            .background()
+            .fill_solid(&marker, BinaryColor::On)
+            .unwrap();
+        let again = drawn(&mut readout, 3, &mut engine);
+        assert_eq!(again.len(), first.len() + 1);
+
+        readout.invalidate();
+        assert_eq!(drawn(&mut readout, 3, &mut engine), first);
+    }
+
+    #[test]
+    fn clears_wider_text() {
+        let format = Format::Number {
+            width: 0,
+            pad: Pad::Spaces,
+        };
+        let mut engine = OneBit::new(Orientation::Portrait);
+        let mut readout = readout(format);
+        drawn(&mut readout, 123456, &mut engine);
+        let narrow = drawn(&mut readout, 5, &mut engine);
+
+        let mut fresh = OneBit::new(Orientation::Portrait);
+        assert_eq!(narrow, drawn(&mut self::readout(format), 5, &mut fresh));
+    }
+}
@@ -1,9 +1,25 @@
 #![cfg_attr(not(test), no_std)]
 
+pub mod anim;
+pub mod assets;
+pub mod background;
+pub mod camera;
+pub mod dither;
+mod effects;
+pub mod fonts;
+mod game_over;
 pub mod games;
-mod hi_score;
+pub mod gray;
+pub mod hud;
+pub mod hi_score;
 pub mod menu;
-mod util;
+pub mod particles;
+pub mod scene;
+pub mod splash;
+pub mod tilemap;
+pub mod transition;
+pub mod util;
+pub mod widgets;
 mod ui;
 
 use core::convert::Infallible;
@@ -15,11 +31,51 @@ use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::Rectangle;
 use embedded_graphics::Pixel;
 use frugger_core::{FrugTimer, FruggerEngine, FruggerGame, Orientation};
+use crate::background::Background;
+use crate::effects::Shake;
+use crate::gray::{GrayLayer, GrayPlanes};
 use crate::menu::SaveOffset;
+use crate::transition::{Active, BitFrame, Transition};
 
 #[derive(Clone)]
 pub enum Signal {
-    Save { score: u16, save_offset: SaveOffset },
+    /// The game has ended, `save_offset` is where its high scores are kept.
+    GameOver { score: u16, save_offset: SaveOffset },
+    /// Start the same game again.
+    Retry,
+    /// Back to the menu.
+    Quit,
+}
+
+/// How drawn pixels combine with what is already in the frame.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub enum Blend {
+    #[default]
+    Replace,
+    /// Off pixels are transparent.
+    Or,
+    And,
+    Xor,
+    /// On pixels flip what is underneath, Off pixels are transparent.
+    Invert,
+}
+
+impl Blend {
+    fn apply(&self, dst: BinaryColor, src: BinaryColor) -> BinaryColor {
+        match self {
+            Blend::Replace => src,
+            Blend::Or => (dst.is_on() || src.is_on()).into(),
+            Blend::And => (dst.is_on() && src.is_on()).into(),
+            Blend::Xor => (dst.is_on() != src.is_on()).into(),
+            Blend::Invert => {
+                if src.is_on() {
+                    dst.invert()
+                } else {
+                    dst
+                }
+            }
+        }
+    }
 }
 
 pub struct OneBit {
@@ -28,6 +84,22 @@ pub struct OneBit {
     scr_width: usize,
     orientation: Orientation,
     signal: Option<Signal>,
+    blend: Blend,
+    transition: Option<Active>,
+    /// Only in gray mode.
+    gray: Option<GrayPlanes>,
+    subframe: u8,
+    /// Retained layer each frame starts from, instead of all Off.
+    background: Option<BitFrame>,
+    /// Added to everything drawn.
+    offset: Point,
+    shake: Option<Shake>,
+    shake_offset: Point,
+    /// Frames ended so far, seeds each shake.
+    frame: u32,
+    invert_frames: u16,
+    /// Send every pixel next frame, not just the changed ones.
+    redraw: bool,
 }
 
 impl OneBit {
@@ -41,12 +113,135 @@ impl OneBit {
             },
             orientation,
             signal: None,
+            blend: Blend::Replace,
+            transition: None,
+            gray: None,
+            subframe: 0,
+            background: None,
+            offset: Point::zero(),
+            shake: None,
+            shake_offset: Point::zero(),
+            frame: 0,
+            invert_frames: 0,
+            redraw: false,
         }
     }
 
+    /// Applies to everything drawn until it is changed again.
+    pub fn set_blend(&mut self, blend: Blend) {
+        self.blend = blend;
+    }
+
+    /// Start the frame from all Off and send all of it to the display, for when something else
+    /// has drawn on the display.
     pub fn clear_buffer(&mut self) {
-        self.last_frame = [BinaryColor::On; 8192];
         self.next_frame = [BinaryColor::Off; 8192];
+        self.redraw = true;
+    }
+
+    /// What is on the display right now.
+    pub fn snapshot(&self) -> BitFrame {
+        BitFrame::from_colors(&self.last_frame)
+    }
+
+    /// Transition over `frames` from `from`, which has to be what the display is showing, usually
+    /// the `snapshot` of the engine that drew last. Drawing carries on as normal meanwhile.
+    pub fn transition_from(&mut self, from: BitFrame, kind: Transition, frames: u16) {
+        for (idx, col) in self.last_frame.iter_mut().enumerate() {
+            *col = from.get(idx);
+        }
+        self.transition = Some(Active::new(kind, from, frames));
+    }
+
+    pub fn transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Gray mode adds a 2 bit layer that is flashed over `subframes` flushes per frame. The
+    /// panel needs flushing fast enough for it to blend, so it halves the frame rate or worse.
+    pub fn set_gray(&mut self, enabled: bool) {
+        if self.subframe != 0 {
+            // Part way through a gray frame, `next_frame` still holds it with the transition and
+            // flash already applied. Drop it so the next frame starts clean.
+            self.subframe = 0;
+            self.end_frame();
+        }
+        self.gray = enabled.then(GrayPlanes::new);
+    }
+
+    /// Draw static art once instead of every frame. Starts empty the first time it is used. It
+    /// shakes with everything else, but stays put under `set_offset`.
+    pub fn background(&mut self) -> Background<'_> {
+        let bounds = self.bounding_box();
+        Background {
+            layer: self.background.get_or_insert_with(BitFrame::new),
+            next_frame: &mut self.next_frame,
+            bounds,
+            shift: self.shake_offset,
+        }
+    }
+
+    /// Drop the background, frames start from all Off again after this one.
+    pub fn clear_background(&mut self) {
+        self.background = None;
+    }
+
+    /// Move everything drawn from now on by `offset`, on top of any shake.
+    pub fn set_offset(&mut self, offset: Point) {
+        self.offset = offset;
+    }
+
+    pub fn offset(&self) -> Point {
+        self.offset
+    }
+
+    /// Jitter everything drawn by up to `amplitude` pixels, dying off over `frames`. Replaces any
+    /// shake already going.
+    pub fn shake(&mut self, amplitude: u8, frames: u16) {
+        self.shake = Some(Shake::new(amplitude, frames, self.frame));
+    }
+
+    /// Invert the whole screen for `frames`.
+    pub fn flash(&mut self, frames: u16) {
+        self.invert_frames = frames;
+    }
+
+    /// Start the next frame from the background and move the effects on.
+    fn end_frame(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+        self.shake_offset = match self.shake.as_mut().and_then(Shake::tick) {
+            Some(offset) => offset,
+            None => {
+                self.shake = None;
+                Point::zero()
+            }
+        };
+
+        let bounds = self.bounding_box();
+        match &self.background {
+            Some(background) => {
+                for (idx, col) in self.next_frame.iter_mut().enumerate() {
+                    let from = Point::new(
+                        (idx % self.scr_width) as i32,
+                        (idx / self.scr_width) as i32,
+                    ) - self.shake_offset;
+                    *col = if bounds.contains(from) {
+                        background.get(from.y as usize * self.scr_width + from.x as usize)
+                    } else {
+                        BinaryColor::Off
+                    };
+                }
+            }
+            None => self.next_frame.fill(BinaryColor::Off),
+        }
+    }
+
+    /// The gray layer to draw on, `None` outside gray mode.
+    pub fn gray(&mut self) -> Option<GrayLayer<'_>> {
+        let bounds = self.bounding_box();
+        self.gray
+            .as_mut()
+            .map(|planes| GrayLayer { planes, bounds })
     }
 }
 
@@ -68,7 +263,9 @@ impl DrawTarget for OneBit {
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
         let Size { width, height } = self.bounding_box().size;
+        let shift = self.offset + self.shake_offset;
         for Pixel(point, col) in pixels {
+            let point = point + shift;
             if point.x < 0
                 || point.x > (width - 1) as i32
                 || point.y < 0
@@ -77,7 +274,7 @@ impl DrawTarget for OneBit {
                 continue;
             }
             let idx = (point.y * width as i32 + point.x) as usize;
-            self.next_frame[idx] = col;
+            self.next_frame[idx] = self.blend.apply(self.next_frame[idx], col);
         }
         Ok(())
     }
@@ -88,10 +285,52 @@ impl FruggerEngine<BinaryColor> for OneBit {
     where
         T: DrawTarget<Color = BinaryColor>,
     {
+        if self.subframe == 0 {
+            if let Some(transition) = &mut self.transition {
+                let running = transition.tick();
+                transition.compose(&mut self.next_frame, self.scr_width);
+                if !running {
+                    self.transition = None;
+                }
+            }
+
+            if self.invert_frames > 0 {
+                self.invert_frames -= 1;
+                for col in self.next_frame.iter_mut() {
+                    *col = col.invert();
+                }
+            }
+        }
+
+        if let Some(planes) = &mut self.gray {
+            // Keep the frame around for the next subframes, so no swapping
+            for (idx, col) in self.next_frame.iter().enumerate() {
+                let col = (col.is_on() || planes.on(idx, self.subframe)).into();
+                if self.redraw || self.last_frame[idx] != col {
+                    let x = idx % self.scr_width;
+                    let y = idx / self.scr_width;
+                    let _ = display.fill_solid(
+                        &Rectangle::new(Point::new(x as _, y as _), Size::new_equal(1)),
+                        col,
+                    );
+                    self.last_frame[idx] = col;
+                }
+            }
+
+            self.redraw = false;
+            self.subframe += 1;
+            if self.subframe == GrayPlanes::SUBFRAMES {
+                self.subframe = 0;
+                planes.clear();
+                self.end_frame();
+            }
+            return;
+        }
+
         for (idx, col) in self.next_frame.iter().enumerate() {
             let x = idx % self.scr_width;
             let y = idx / self.scr_width;
-            if &self.last_frame[idx] != col {
+            if self.redraw || &self.last_frame[idx] != col {
                 display.fill_solid(
                     &Rectangle::new(Point::new(x as _, y as _), Size::new_equal(1)),
                     *col,
@@ -100,6 +339,41 @@ impl FruggerEngine<BinaryColor> for OneBit {
         }
 
         mem::swap(&mut self.next_frame, &mut self.last_frame);
-        self.next_frame.fill(BinaryColor::Off);
+        self.redraw = false;
+        self.end_frame();
+    }
+
+    fn subframes(&self) -> u8 {
+        match self.gray {
+            Some(_) => GrayPlanes::SUBFRAMES,
+            None => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The first four pixels after drawing Off, Off, On, On over Off, On, Off, On.
+    fn blended(blend: Blend) -> [bool; 4] {
+        let mut engine = OneBit::new(Orientation::Landscape);
+        let row = |cols: [bool; 4]| {
+            (0..4).map(move |x| Pixel(Point::new(x, 0), BinaryColor::from(cols[x as usize])))
+        };
+        engine.draw_iter(row([false, true, false, true])).unwrap();
+        engine.set_blend(blend);
+        engine.draw_iter(row([false, false, true, true])).unwrap();
+        core::array::from_fn(|idx| engine.next_frame[idx].is_on())
+    }
+
+    #[test]
+    fn blends() {
+        assert_eq!(blended(Blend::Replace), [false, false, true, true]);
+        assert_eq!(blended(Blend::Or), [false, true, true, true]);
+        assert_eq!(blended(Blend::And), [false, false, false, true]);
+        assert_eq!(blended(Blend::Xor), [false, true, true, false]);
+        // Same as Xor with one bit
+        assert_eq!(blended(Blend::Invert), [false, true, true, false]);
     }
 }
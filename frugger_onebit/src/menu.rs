@@ -4,20 +4,21 @@ use crate::games::racer::Racer;
 use crate::games::runner::Runner;
 use crate::games::triangle_jump::Jump;
 use crate::games::worm::SmolWorm;
+use crate::game_over::GameOver;
 use crate::hi_score::HiScore;
+use crate::scene::{Scene, SceneOp, SceneStack};
+use crate::splash::Splash;
+use crate::transition::{BitFrame, Transition};
 use crate::{OneBit, Signal};
+use crate::widgets::List;
 use embedded_graphics::geometry::Point;
 use embedded_graphics::mono_font::ascii::FONT_7X13;
-use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
-use embedded_graphics::primitives::{PrimitiveStyle, Rectangle, StyledDrawable};
-use embedded_graphics::text::{Alignment, Text};
-use embedded_graphics::Drawable;
+use embedded_graphics::primitives::Rectangle;
 use frugger_core::{FrugInputs, FruggerGame, Orientation};
 
 pub enum Game {
-    Scores(HiScore),
     InputTest(InputTestSmall),
     MatchMe(MatchMe),
     Racer(Racer),
@@ -30,6 +31,8 @@ pub enum Game {
 pub enum SaveOffset {
     TriangleScores = 0,
     MatchScores = 1,
+    RacerScores = 2,
+    WormScores = 3,
 }
 
 impl FruggerGame for Game {
@@ -40,7 +43,6 @@ impl FruggerGame for Game {
 
     fn update(&mut self, inputs: &FrugInputs) {
         match self {
-            Game::Scores(game) => game.update(inputs),
             Game::InputTest(game) => game.update(inputs),
             Game::MatchMe(game) => game.update(inputs),
             Game::Racer(game) => game.update(inputs),
@@ -52,7 +54,6 @@ impl FruggerGame for Game {
 
     fn frugger(&mut self) -> &mut Self::Engine {
         match self {
-            Game::Scores(game) => game.frugger(),
             Game::InputTest(game) => game.frugger(),
             Game::MatchMe(game) => game.frugger(),
             Game::Racer(game) => game.frugger(),
@@ -62,11 +63,41 @@ impl FruggerGame for Game {
         }
     }
 }
+
+/// Screens pushed on top of the menu or the running game.
+pub enum Screen {
+    Splash(Splash<'static>),
+    Scores(HiScore),
+    GameOver(GameOver),
+}
+
+impl Scene for Screen {
+    fn update(&mut self, inputs: &FrugInputs, engine: &mut OneBit) -> SceneOp<Self> {
+        match self {
+            Screen::Splash(splash) => {
+                if splash.update(inputs, engine) {
+                    return SceneOp::Pop;
+                }
+            }
+            Screen::Scores(scores) => {
+                if scores.update(inputs, engine) {
+                    let over = GameOver::new(scores.score(), scores.best());
+                    return SceneOp::Replace(Screen::GameOver(over));
+                }
+            }
+            Screen::GameOver(over) => over.update(inputs, engine),
+        }
+        SceneOp::None
+    }
+}
+
 pub struct Menu {
     engine: OneBit,
     curr_game: Option<Game>,
-    game_changed: bool,
-    selection: u8,
+    scenes: SceneStack<Screen, 4>,
+    /// Set when the screen changes, with what was showing before.
+    change: Option<(Transition, BitFrame)>,
+    games: List<'static>,
     ticks: u64,
     pause_start: u64,
     pub load: fn() -> [u8; 1024],
@@ -75,17 +106,78 @@ pub struct Menu {
 
 impl Menu {
     pub fn new(load: fn() -> [u8; 1024], save: fn(usize, [u8; 32])) -> Self {
+        let mut engine = OneBit::new(Self::ORIENTATION);
+        let games = List::new(&Self::GAMES, &FONT_7X13);
+        // The game names never move, so they live in the engine's background
+        games.draw_items(&Self::LIST, &mut engine.background()).unwrap();
+
         Self {
-            engine: OneBit::new(Self::ORIENTATION),
-            selection: 0,
-            game_changed: false,
+            engine,
+            games,
+            change: None,
             curr_game: None,
+            scenes: SceneStack::new(),
             ticks: 0,
             pause_start: 0,
             load,
             save,
         }
     }
+
+    /// Show `splash` before the menu.
+    pub fn splash(mut self, splash: Splash<'static>) -> Self {
+        // The game names come back when it is done
+        self.engine.clear_background();
+        self.scenes.show(Screen::Splash(splash));
+        self
+    }
+
+    /// Whether every game's saved high scores look intact.
+    pub fn storage_ok(&self) -> bool {
+        let loaded = (self.load)();
+        [
+            SaveOffset::TriangleScores,
+            SaveOffset::MatchScores,
+            SaveOffset::RacerScores,
+            SaveOffset::WormScores,
+        ]
+        .into_iter()
+        .all(|save_offset| {
+            let offset = save_offset as usize * 32;
+            HiScore::valid(&loaded[offset..offset + 32])
+        })
+    }
+
+    const TRANSITION_FRAMES: u16 = 24;
+
+    /// Call before switching away, the next engine to draw transitions from what is on screen.
+    fn change_screen(&mut self, kind: Transition) {
+        let engine = match &mut self.curr_game {
+            Some(game) => game.frugger(),
+            None => &mut self.engine,
+        };
+        self.change = Some((kind, engine.snapshot()));
+    }
+
+    fn back_to_menu(&mut self) {
+        self.change_screen(Transition::Wipe);
+        self.curr_game = None;
+        self.scenes.clear();
+    }
+
+    /// Start the selected game, from scratch if it was already running.
+    fn start_game(&mut self) {
+        self.curr_game = match self.games.selected() {
+            0 => Some(Game::TriangleJump(Jump::new(self.ticks))),
+            1 => Some(Game::Worm(SmolWorm::new(self.ticks))),
+            2 => Some(Game::Racer(Racer::new(self.ticks))),
+            3 => Some(Game::MatchMe(MatchMe::new(self.ticks))),
+            _ => None,
+        };
+    }
+
+    const GAMES: [&'static str; 4] = ["Jump", "Worm", "Racer", "Match"];
+    const LIST: Rectangle = Rectangle::new(Point::new(1, 19), Size::new(62, 60));
 }
 
 impl FruggerGame for Menu {
@@ -100,9 +192,7 @@ impl FruggerGame for Menu {
         if inputs.left.down() && inputs.right.down() {
             self.pause_start += 1;
             if self.pause_start == 120 {
-                // Force a full screen redraw
-                self.game_changed = true;
-                self.curr_game = None;
+                self.back_to_menu();
                 return;
             }
         } else {
@@ -112,87 +202,95 @@ impl FruggerGame for Menu {
         if let Some(signal) = self
             .curr_game
             .as_mut()
-            .and_then(|game| game.frugger().signal.clone())
+            .and_then(|game| game.frugger().signal.take())
         {
             match signal {
-                Signal::Save { save_offset, score } => {
+                Signal::GameOver { save_offset, score } => {
+                    // Name entry first if the score makes the table
                     let offset = save_offset as usize * 32;
-                    self.curr_game = Some(Game::Scores(HiScore::new(
-                        &(self.load)()[offset..offset + 32],
-                        score,
-                        self.save,
-                    )));
+                    let loaded = (self.load)();
+                    let table = &loaded[offset..offset + 32];
+                    let screen = if HiScore::qualifies(table, score) {
+                        Screen::Scores(HiScore::new(table, score, offset, self.save))
+                    } else {
+                        Screen::GameOver(GameOver::new(score, HiScore::best_in(table)))
+                    };
+                    self.scenes.show(screen);
 
-                    self.game_changed = true;
+                    self.change_screen(Transition::FadeThroughBlack);
+                    // The screens draw on the game's engine, drop its HUD
+                    if let Some(game) = &mut self.curr_game {
+                        game.frugger().clear_background();
+                    }
+                    return;
+                }
+                Signal::Retry => {
+                    self.change_screen(Transition::Slide);
+                    self.scenes.clear();
+                    self.start_game();
+                    return;
+                }
+                Signal::Quit => {
+                    self.back_to_menu();
                     return;
                 }
             }
         }
 
+        if !self.scenes.is_empty() {
+            // Keep whatever is under the stack going, but without input
+            let see_through = self.scenes.see_through();
+            let engine = match &mut self.curr_game {
+                Some(game) => {
+                    if see_through {
+                        game.update(&FrugInputs::default());
+                    }
+                    game.frugger()
+                }
+                None => {
+                    if see_through {
+                        self.games.draw_cursor(&Self::LIST, &mut self.engine).unwrap();
+                    }
+                    &mut self.engine
+                }
+            };
+
+            if self.scenes.tick(inputs, engine) {
+                self.change_screen(Transition::Dissolve);
+            }
+            if self.scenes.is_empty() && self.curr_game.is_none() {
+                // Left the splash, it drew over the game names
+                self.engine.clear_background();
+                self.games
+                    .draw_items(&Self::LIST, &mut self.engine.background())
+                    .unwrap();
+            }
+            return;
+        }
+
         if let Some(game) = &mut self.curr_game {
             game.update(inputs);
             return;
         }
 
-        // Inputs
-        if inputs.right.pressed() {
-            self.selection = (self.selection + 1) % 4;
-        } else if inputs.left.pressed() {
-            self.selection = (self.selection + 3) % 4;
-        } else if inputs.a.pressed() {
-            // start the game
-            self.curr_game = match self.selection {
-                0 => Some(Game::TriangleJump(Jump::new(self.ticks))),
-                1 => Some(Game::Worm(SmolWorm::new(self.ticks))),
-                2 => Some(Game::Racer(Racer::new(self.ticks))),
-                3 => Some(Game::MatchMe(MatchMe::new(self.ticks))),
-                _ => None,
-            };
-
-            self.game_changed = true;
+        if self.games.update(inputs).is_some() {
+            self.change_screen(Transition::Slide);
+            self.start_game();
             return;
         }
 
-        // Draw menu
-        let txt_style = MonoTextStyle::new(&FONT_7X13, BinaryColor::On);
-
-        let mut text = Text::new("Jump", Point::new(32, 30), txt_style);
-        text.text_style.alignment = Alignment::Center;
-        text.draw(&mut self.engine).unwrap();
-
-        let mut text = Text::new("Worm", Point::new(32, 45), txt_style);
-        text.text_style.alignment = Alignment::Center;
-        text.draw(&mut self.engine).unwrap();
-
-        let mut text = Text::new("Racer", Point::new(32, 60), txt_style);
-        text.text_style.alignment = Alignment::Center;
-        text.draw(&mut self.engine).unwrap();
-
-        let mut text = Text::new("Match", Point::new(32, 75), txt_style);
-        text.text_style.alignment = Alignment::Center;
-        text.draw(&mut self.engine).unwrap();
-
-        let mut sel = Rectangle::new(Point::new(1, 18), Size::new(62, 17));
-        sel.translate_mut(Point::new(0, (self.selection * 15) as i32));
-
-        sel.draw_styled(
-            &PrimitiveStyle::with_stroke(BinaryColor::On, 1),
-            &mut self.engine,
-        )
-        .unwrap()
+        self.games.draw_cursor(&Self::LIST, &mut self.engine).unwrap();
     }
 
     fn frugger(&mut self) -> &mut Self::Engine {
-
         let engine = if let Some(game) = &mut self.curr_game {
             game.frugger()
         } else {
             &mut self.engine
         };
 
-        if self.game_changed {
-            self.game_changed = false;
-            engine.clear_buffer()
+        if let Some((kind, from)) = self.change.take() {
+            engine.transition_from(from, kind, Self::TRANSITION_FRAMES);
         }
 
         engine
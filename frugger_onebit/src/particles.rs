@@ -0,0 +1,218 @@
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, Line, PrimitiveStyle, Rectangle};
+use frugger_core::fixed::{Fx, FxVec2};
+use heapless::Vec;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+/// How each particle is drawn.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Shape {
+    Dot,
+    /// Filled square of the given size.
+    Square(u32),
+    /// Circle outline of the given diameter.
+    Circle(u32),
+    /// Line back along the velocity, a motion trail.
+    Streak,
+}
+
+/// Everything about the particles a burst spawns. Speeds and gravity are in pixels per frame.
+#[derive(Copy, Clone)]
+pub struct Emission {
+    /// Frames to live, picked between the two. At least 1.
+    pub life: (u8, u8),
+    pub speed: (Fx, Fx),
+    /// Angle in radians to fire at, 0 is right and positive is clockwise (down the screen).
+    pub direction: Fx,
+    /// Angle around `direction` particles are spread over, `Fx::TAU` for every direction.
+    pub spread: Fx,
+    /// Added to the vertical velocity every frame.
+    pub gravity: Fx,
+    pub shape: Shape,
+    /// Show every other frame for the last quarter of the particle's life.
+    pub flicker: bool,
+}
+
+impl Emission {
+    /// Fast ring of streaks in every direction.
+    pub const EXPLOSION: Emission = Emission {
+        life: (15, 35),
+        speed: (Fx::from_f32(0.5), Fx::from_f32(2.0)),
+        direction: Fx::ZERO,
+        spread: Fx::TAU,
+        gravity: Fx::from_f32(0.04),
+        shape: Shape::Streak,
+        flicker: true,
+    };
+
+    /// Slow puffs kicked up sideways off the ground.
+    pub const DUST: Emission = Emission {
+        life: (6, 14),
+        speed: (Fx::from_f32(0.2), Fx::from_f32(0.7)),
+        direction: Fx::from_f32(-core::f32::consts::FRAC_PI_2),
+        spread: Fx::PI,
+        gravity: Fx::from_f32(0.03),
+        shape: Shape::Dot,
+        flicker: false,
+    };
+
+    /// Drifting twinkles that hang in the air.
+    pub const SPARKLE: Emission = Emission {
+        life: (20, 40),
+        speed: (Fx::from_f32(0.05), Fx::from_f32(0.3)),
+        direction: Fx::ZERO,
+        spread: Fx::TAU,
+        gravity: Fx::ZERO,
+        shape: Shape::Dot,
+        flicker: true,
+    };
+}
+
+#[derive(Copy, Clone)]
+struct Particle {
+    pos: FxVec2,
+    vel: FxVec2,
+    life: u8,
+    max_life: u8,
+    emission: Emission,
+}
+
+/// Fixed size pool of particles. Bursts are dropped once it's full, so size it for the worst case
+/// you care about and the rest just won't appear.
+pub struct Particles<const N: usize> {
+    particles: Vec<Particle, N>,
+    frame: u8,
+    /// Its own, so effects never change what the game rolls next.
+    rng: SmallRng,
+}
+
+impl<const N: usize> Particles<N> {
+    /// Seed it from the game's seed so runs repeat.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            particles: Vec::new(),
+            frame: 0,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Spawn `count` particles at `pos`.
+    pub fn burst(&mut self, pos: Point, count: usize, emission: &Emission) {
+        let origin = FxVec2::new(Fx::from_int(pos.x), Fx::from_int(pos.y));
+        let half_spread = emission.spread / 2;
+        let rng = &mut self.rng;
+
+        for _ in 0..count {
+            let angle = emission.direction + random(rng, -half_spread, half_spread);
+            let speed = random(rng, emission.speed.0, emission.speed.1);
+            let shortest = emission.life.0.max(1);
+            let life = rng.gen_range(shortest..=emission.life.1.max(shortest));
+
+            let particle = Particle {
+                pos: origin,
+                vel: FxVec2::from_angle(angle) * speed,
+                life,
+                max_life: life,
+                emission: *emission,
+            };
+            if self.particles.push(particle).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Move everything on a frame and remove the dead.
+    pub fn update(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+        self.particles.retain_mut(|p| {
+            p.vel.y += p.emission.gravity;
+            p.pos += p.vel;
+            p.life = p.life.saturating_sub(1);
+            p.life > 0
+        });
+    }
+
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        for p in &self.particles {
+            if p.emission.flicker && p.life < p.max_life / 4 && self.frame % 2 == 0 {
+                continue;
+            }
+
+            let (x, y) = p.pos.round();
+            let pos = Point::new(x, y);
+            match p.emission.shape {
+                Shape::Dot => Pixel(pos, BinaryColor::On).draw(target)?,
+                Shape::Square(size) => {
+                    let size = Size::new_equal(size);
+                    target.fill_solid(&Rectangle::with_center(pos, size), BinaryColor::On)?
+                }
+                Shape::Circle(diameter) => Circle::with_center(pos, diameter)
+                    .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+                    .draw(target)?,
+                Shape::Streak => {
+                    let (tx, ty) = (p.pos - p.vel).round();
+                    Line::new(pos, Point::new(tx, ty))
+                        .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+                        .draw(target)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+}
+
+impl<const N: usize> Default for Particles<N> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+fn random<R: Rng>(rng: &mut R, min: Fx, max: Fx) -> Fx {
+    if min >= max {
+        return min;
+    }
+    Fx::from_raw(rng.gen_range(min.raw()..=max.raw()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_life_lasts_a_frame() {
+        let emission = Emission {
+            life: (0, 0),
+            ..Emission::DUST
+        };
+        let mut particles: Particles<8> = Particles::new(1);
+        particles.burst(Point::zero(), 4, &emission);
+        assert_eq!(particles.len(), 4);
+        particles.update();
+        assert!(particles.is_empty());
+    }
+
+    #[test]
+    fn full_pool_drops_the_rest() {
+        let mut particles: Particles<8> = Particles::new(1);
+        particles.burst(Point::zero(), 6, &Emission::SPARKLE);
+        particles.burst(Point::zero(), 6, &Emission::SPARKLE);
+        assert_eq!(particles.len(), 8);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::OneBit;
+use frugger_core::FrugInputs;
+use heapless::Vec;
+
+/// What the stack should do after a scene has updated.
+pub enum SceneOp<S> {
+    None,
+    Push(S),
+    Pop,
+    Replace(S),
+}
+
+pub trait Scene: Sized {
+    /// Only called for the scene on top of the stack, which is the only one receiving input.
+    fn update(&mut self, inputs: &FrugInputs, engine: &mut OneBit) -> SceneOp<Self>;
+
+    /// Draw without updating, called while this scene is showing under an overlay.
+    fn draw(&mut self, _engine: &mut OneBit) {}
+
+    /// Overlays let the scenes underneath keep rendering.
+    fn overlay(&self) -> bool {
+        false
+    }
+}
+
+/// Push/pop stack of scenes, the top one is active.
+pub struct SceneStack<S, const N: usize> {
+    scenes: Vec<S, N>,
+}
+
+impl<S: Scene, const N: usize> SceneStack<S, N> {
+    pub fn new() -> Self {
+        Self { scenes: Vec::new() }
+    }
+
+    /// Returns the scene back if the stack is full.
+    pub fn push(&mut self, scene: S) -> Result<(), S> {
+        self.scenes.push(scene)
+    }
+
+    /// Push `scene`, or if the stack is full put it in place of the top one, so it shows either
+    /// way. A full stack is a bug, it asserts in debug builds.
+    pub fn show(&mut self, scene: S) {
+        debug_assert!(self.scenes.len() < N, "scene stack is full");
+        if let Err(scene) = self.push(scene) {
+            self.replace(scene);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<S> {
+        self.scenes.pop()
+    }
+
+    pub fn replace(&mut self, scene: S) -> Option<S> {
+        let old = self.scenes.pop();
+        // Can't be full, we just popped (or it was empty).
+        let _ = self.scenes.push(scene);
+        old
+    }
+
+    pub fn clear(&mut self) {
+        self.scenes.clear();
+    }
+
+    pub fn top(&mut self) -> Option<&mut S> {
+        self.scenes.last_mut()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// True if every scene on the stack is an overlay, so whatever is under the stack should
+    /// still be drawn.
+    pub fn see_through(&self) -> bool {
+        self.scenes.iter().all(|scene| scene.overlay())
+    }
+
+    /// Draw the visible scenes below the top, then update the top and apply its op.
+    /// Returns true if the stack changed.
+    pub fn tick(&mut self, inputs: &FrugInputs, engine: &mut OneBit) -> bool {
+        let Some(top) = self.scenes.len().checked_sub(1) else {
+            return false;
+        };
+
+        // Find the lowest scene that can be seen from the top.
+        let mut bottom = top;
+        while bottom > 0 && self.scenes[bottom].overlay() {
+            bottom -= 1;
+        }
+
+        for scene in &mut self.scenes[bottom..top] {
+            scene.draw(engine);
+        }
+
+        match self.scenes[top].update(inputs, engine) {
+            SceneOp::None => false,
+            SceneOp::Push(scene) => {
+                self.show(scene);
+                true
+            }
+            SceneOp::Pop => {
+                self.pop();
+                true
+            }
+            SceneOp::Replace(scene) => {
+                self.replace(scene);
+                true
+            }
+        }
+    }
+}
+
+impl<S: Scene, const N: usize> Default for SceneStack<S, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::RefCell;
+    use frugger_core::Orientation;
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        /// Scene ids in the order they were drawn or updated, updates negated.
+        static LOG: RefCell<Vec<i8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn log() -> Vec<i8> {
+        LOG.with(|log| log.take())
+    }
+
+    struct Test {
+        id: i8,
+        overlay: bool,
+        /// Returned from the next update.
+        op: Option<Box<SceneOp<Test>>>,
+    }
+
+    impl Test {
+        fn new(id: i8) -> Self {
+            Self {
+                id,
+                overlay: false,
+                op: None,
+            }
+        }
+
+        fn overlay(id: i8) -> Self {
+            Self {
+                overlay: true,
+                ..Self::new(id)
+            }
+        }
+
+        fn then(mut self, op: SceneOp<Test>) -> Self {
+            self.op = Some(Box::new(op));
+            self
+        }
+    }
+
+    impl Scene for Test {
+        fn update(&mut self, _inputs: &FrugInputs, _engine: &mut OneBit) -> SceneOp<Self> {
+            LOG.with(|log| log.borrow_mut().push(-self.id));
+            self.op.take().map_or(SceneOp::None, |op| *op)
+        }
+
+        fn draw(&mut self, _engine: &mut OneBit) {
+            LOG.with(|log| log.borrow_mut().push(self.id));
+        }
+
+        fn overlay(&self) -> bool {
+            self.overlay
+        }
+    }
+
+    fn ids<const N: usize>(stack: &mut SceneStack<Test, N>) -> Vec<i8> {
+        let mut ids = Vec::new();
+        while let Some(scene) = stack.pop() {
+            ids.push(scene.id);
+        }
+        ids
+    }
+
+    #[test]
+    fn push_pop_replace() {
+        let mut stack: SceneStack<Test, 4> = SceneStack::new();
+        assert!(stack.push(Test::new(1)).is_ok());
+        assert!(stack.push(Test::new(2)).is_ok());
+        assert_eq!(stack.replace(Test::new(3)).map(|s| s.id), Some(2));
+        assert_eq!(stack.top().map(|s| s.id), Some(3));
+        assert_eq!(ids(&mut stack), [3, 1]);
+        assert!(stack.is_empty());
+        assert!(stack.replace(Test::new(4)).is_none());
+        assert_eq!(stack.len(), 1);
+    }
+
+    #[test]
+    fn tick_applies_the_top_op() {
+        let mut engine = OneBit::new(Orientation::Portrait);
+        let inputs = FrugInputs::default();
+        let mut stack: SceneStack<Test, 4> = SceneStack::new();
+        assert!(!stack.tick(&inputs, &mut engine));
+
+        let _ = stack.push(Test::new(1).then(SceneOp::Push(Test::new(2).then(SceneOp::Pop))));
+        assert!(stack.tick(&inputs, &mut engine));
+        assert_eq!(stack.len(), 2);
+        assert!(stack.tick(&inputs, &mut engine));
+        assert_eq!(stack.len(), 1);
+        assert!(!stack.tick(&inputs, &mut engine));
+
+        stack.top().unwrap().op = Some(Box::new(SceneOp::Replace(Test::new(3))));
+        assert!(stack.tick(&inputs, &mut engine));
+        assert_eq!(log(), [-1, -2, -1, -1]);
+        assert_eq!(ids(&mut stack), [3]);
+    }
+
+    #[test]
+    fn overlays_draw_the_scenes_underneath() {
+        let mut engine = OneBit::new(Orientation::Portrait);
+        let inputs = FrugInputs::default();
+        let mut stack: SceneStack<Test, 5> = SceneStack::new();
+        assert!(stack.see_through());
+
+        // Only the top updates, down to the first scene that isn't an overlay draws
+        let _ = stack.push(Test::new(1));
+        let _ = stack.push(Test::new(2));
+        let _ = stack.push(Test::overlay(3));
+        let _ = stack.push(Test::overlay(4));
+        assert!(!stack.see_through());
+        stack.tick(&inputs, &mut engine);
+        assert_eq!(log(), [2, 3, -4]);
+
+        // Not an overlay, so nothing under it
+        let _ = stack.push(Test::new(5));
+        stack.tick(&inputs, &mut engine);
+        assert_eq!(log(), [-5]);
+
+        stack.clear();
+        let _ = stack.push(Test::overlay(6));
+        assert!(stack.see_through());
+    }
+
+    #[test]
+    fn full_stack() {
+        let mut stack: SceneStack<Test, 2> = SceneStack::new();
+        let _ = stack.push(Test::new(1));
+        let _ = stack.push(Test::new(2));
+        assert_eq!(stack.push(Test::new(3)).map_err(|s| s.id), Err(3));
+        assert_eq!(stack.len(), 2);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "scene stack is full")]
+    fn full_stack_push_op_asserts() {
+        let mut engine = OneBit::new(Orientation::Portrait);
+        let mut stack: SceneStack<Test, 1> = SceneStack::new();
+        let _ = stack.push(Test::new(1).then(SceneOp::Push(Test::new(2))));
+        stack.tick(&FrugInputs::default(), &mut engine);
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn full_stack_shows_the_new_scene() {
+        let mut stack: SceneStack<Test, 1> = SceneStack::new();
+        let _ = stack.push(Test::new(1));
+        stack.show(Test::new(2));
+        assert_eq!(ids(&mut stack), [2]);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::dither::{level, Pattern, PatternExt};
+use crate::fonts::{PropTextStyle, FONT_PROP, FONT_SMALL, FONT_TINY};
+use crate::util::Sprite;
+use crate::widgets::Label;
+use crate::OneBit;
+use embedded_graphics::mono_font::ascii::FONT_8X13;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+use frugger_core::num_text::NumText;
+use frugger_core::tween::{Ease, Tween};
+use frugger_core::FrugInputs;
+
+/// Shown at boot: the logo drops in, the title fades up under it, then the version and the
+/// result of a start up check. Any button skips it, a failed check waits for one.
+pub struct Splash<'a> {
+    logo: Sprite<'a>,
+    version: &'a str,
+    /// What was checked and whether it passed.
+    status: Option<(&'a str, bool)>,
+    frames: u16,
+    elapsed: u16,
+    drop: Tween,
+}
+
+impl<'a> Splash<'a> {
+    const LOGO_Y: i32 = 30;
+    const DROP_FRAMES: u16 = 30;
+    const FADE_FRAMES: u16 = 16;
+    const TITLE: Rectangle = Rectangle::new(Point::new(0, 56), Size::new(64, 13));
+    const STATUS: Rectangle = Rectangle::new(Point::new(0, 96), Size::new(64, 6));
+    const PROMPT: Rectangle = Rectangle::new(Point::new(0, 106), Size::new(64, 5));
+    const VERSION: Rectangle = Rectangle::new(Point::new(0, 120), Size::new(64, 5));
+
+    pub fn new(logo: Sprite<'a>, version: &'a str) -> Self {
+        let height = logo.size().height as f32;
+        Self {
+            logo,
+            version,
+            status: None,
+            frames: 120,
+            elapsed: 0,
+            drop: Tween::new(
+                -height,
+                Self::LOGO_Y as f32,
+                Self::DROP_FRAMES,
+                Ease::BounceOut,
+            ),
+        }
+    }
+
+    /// How long it shows for when nothing is pressed, at least as long as the animation.
+    pub fn frames(mut self, frames: u16) -> Self {
+        self.frames = frames.max(Self::DROP_FRAMES + Self::FADE_FRAMES);
+        self
+    }
+
+    /// Show `label` with OK or FAIL once the logo is in.
+    pub fn status(mut self, label: &'a str, ok: bool) -> Self {
+        self.status = Some((label, ok));
+        self
+    }
+
+    /// True when it is done.
+    pub fn update(&mut self, inputs: &FrugInputs, engine: &mut OneBit) -> bool {
+        if inputs.any_pressed() {
+            return true;
+        }
+        self.elapsed = self.elapsed.saturating_add(1);
+        self.drop.tick();
+
+        let x = (64 - self.logo.size().width as i32) / 2;
+        self.logo.at(x, self.drop.round()).draw(engine).unwrap();
+
+        let mut version = NumText::new();
+        version.push_str("V").push_str(self.version);
+        Label::new(version.as_str(), &FONT_TINY)
+            .draw(&Self::VERSION, engine)
+            .unwrap();
+
+        let fade = self.elapsed.saturating_sub(Self::DROP_FRAMES);
+        if fade == 0 {
+            return false;
+        }
+        let gray = level(fade as f32 / Self::FADE_FRAMES as f32);
+        Label::new("FRUGGER", &FONT_8X13)
+            .draw(&Self::TITLE, &mut engine.patterned(Pattern::Gray(gray)))
+            .unwrap();
+
+        let failed = match self.status {
+            Some((label, ok)) if fade >= Self::FADE_FRAMES => {
+                let mut text = NumText::new();
+                text.push_str(label)
+                    .push_str(if ok { " OK" } else { " FAIL" });
+                Label::new(text.as_str(), &FONT_SMALL)
+                    .draw(&Self::STATUS, engine)
+                    .unwrap();
+                !ok
+            }
+            _ => false,
+        };
+
+        if failed {
+            // Blink so it is clear the splash is waiting
+            if self.elapsed / 30 % 2 == 0 {
+                Text::with_text_style(
+                    "Press any key",
+                    Self::PROMPT.center(),
+                    PropTextStyle::new(&FONT_PROP, BinaryColor::On),
+                    TextStyleBuilder::new()
+                        .alignment(Alignment::Center)
+                        .baseline(Baseline::Middle)
+                        .build(),
+                )
+                .draw(engine)
+                .unwrap();
+            }
+            return false;
+        }
+        self.elapsed >= self.frames
+    }
+}
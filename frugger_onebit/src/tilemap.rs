@@ -0,0 +1,211 @@
+use crate::anim::SpriteSheet;
+use crate::camera::Camera;
+use crate::util::{Flip, Rotation};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// Grid of tiles drawn from a sheet. Tile 0 is empty, tile n is frame n - 1 of the sheet. Tiles
+/// are the size of the sheet's first frame.
+pub struct Tilemap<'a> {
+    tiles: &'a SpriteSheet<'a>,
+    /// Width in tiles.
+    width: u32,
+    /// Row major tile indices.
+    data: &'a [u8],
+    /// Wrap around horizontally and vertically, for repeating backgrounds.
+    repeat: bool,
+}
+
+impl<'a> Tilemap<'a> {
+    /// Panics unless `data` is one or more whole rows of `width` tiles, at compile time for
+    /// constants.
+    pub const fn new(tiles: &'a SpriteSheet<'a>, width: u32, data: &'a [u8]) -> Self {
+        assert!(width > 0, "tilemap has no width");
+        assert!(!data.is_empty(), "tilemap has no tiles");
+        assert!(
+            data.len() % width as usize == 0,
+            "tilemap data isn't whole rows"
+        );
+        Self {
+            tiles,
+            width,
+            data,
+            repeat: false,
+        }
+    }
+
+    pub const fn repeating(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    pub fn tile_size(&self) -> Size {
+        self.tiles.frame(0).size
+    }
+
+    /// Height in tiles.
+    pub fn height(&self) -> u32 {
+        self.data.len() as u32 / self.width
+    }
+
+    /// Size of the map in pixels.
+    pub fn bounds(&self) -> Rectangle {
+        let tile_size = self.tile_size();
+        Rectangle::new(
+            Point::zero(),
+            Size::new(
+                self.width * tile_size.width,
+                self.height() * tile_size.height,
+            ),
+        )
+    }
+
+    /// Tile index at a tile position, 0 if it is outside a map that doesn't repeat.
+    pub fn tile(&self, col: i32, row: i32) -> u8 {
+        let (width, height) = (self.width as i32, self.height() as i32);
+        let (col, row) = if self.repeat {
+            (col.rem_euclid(width), row.rem_euclid(height))
+        } else if col < 0 || row < 0 || col >= width || row >= height {
+            return 0;
+        } else {
+            (col, row)
+        };
+        self.data[(row * width + col) as usize]
+    }
+
+    /// Tile index under a world position.
+    pub fn tile_at(&self, world: Point) -> u8 {
+        let tile_size = self.tile_size();
+        self.tile(
+            world.x.div_euclid(tile_size.width as i32),
+            world.y.div_euclid(tile_size.height as i32),
+        )
+    }
+
+    /// Draw the tiles the camera can see. Use `Camera::parallax` for background layers.
+    pub fn draw<D>(&self, camera: &Camera, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let tile_size = self.tile_size();
+        let (tw, th) = (tile_size.width as i32, tile_size.height as i32);
+        let view = camera.view();
+        let Some(bottom_right) = view.bottom_right() else {
+            return Ok(());
+        };
+
+        for row in view.top_left.y.div_euclid(th)..=bottom_right.y.div_euclid(th) {
+            for col in view.top_left.x.div_euclid(tw)..=bottom_right.x.div_euclid(tw) {
+                let tile = self.tile(col, row);
+                if tile == 0 {
+                    continue;
+                }
+                let pos = camera.to_screen(Point::new(col * tw, row * th));
+                self.tiles.draw_frame(
+                    tile as usize - 1,
+                    pos,
+                    Flip::None,
+                    Rotation::None,
+                    target,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Sprite;
+    use frugger_core::mock::{Call, Recorder};
+
+    /// Two 4x4 tiles, no mask so each tile is one call.
+    const SHEET: SpriteSheet = SpriteSheet::new(
+        Sprite::new(8, 4, 0, 0, &[0xF0, 0x90, 0x90, 0xF0]),
+        crate::anim::Frames::Grid(Size::new(4, 4)),
+    );
+    #[rustfmt::skip]
+    const MAP: Tilemap = Tilemap::new(&SHEET, 3, &[
+        1, 2, 0,
+        2, 0, 1,
+    ]);
+
+    #[test]
+    fn tiles() {
+        assert_eq!(MAP.tile_size(), Size::new(4, 4));
+        assert_eq!(MAP.height(), 2);
+        assert_eq!(MAP.bounds().size, Size::new(12, 8));
+        assert_eq!(MAP.tile(1, 0), 2);
+        assert_eq!(MAP.tile(2, 1), 1);
+        assert_eq!(MAP.tile(-1, 0), 0);
+        assert_eq!(MAP.tile(3, 0), 0);
+        assert_eq!(MAP.tile_at(Point::new(11, 7)), 1);
+        assert_eq!(MAP.tile_at(Point::new(-1, 0)), 0);
+
+        let repeating = Tilemap::new(&SHEET, 3, MAP.data).repeating();
+        assert_eq!(repeating.tile(-1, 0), 0);
+        assert_eq!(repeating.tile(-1, -1), 1);
+        assert_eq!(repeating.tile(4, 2), 2);
+        assert_eq!(repeating.tile_at(Point::new(-1, -1)), 1);
+    }
+
+    fn drawn(map: &Tilemap, camera: &Camera) -> std::vec::Vec<Call> {
+        let mut display = Recorder::new(camera.view().size, BinaryColor::Off);
+        map.draw(camera, &mut display).unwrap();
+        display.calls().to_vec()
+    }
+
+    #[test]
+    fn draws_only_visible_tiles() {
+        let tile = |x, y| Call::Contiguous(Rectangle::new(Point::new(x, y), Size::new(4, 4)));
+        let mut camera = Camera::new(Size::new(4, 4));
+        assert_eq!(drawn(&MAP, &camera), [tile(0, 0)]);
+
+        // Straddling four tiles, one of them empty
+        camera.pos = Point::new(2, 2);
+        assert_eq!(
+            drawn(&MAP, &camera),
+            [tile(-2, -2), tile(2, -2), tile(-2, 2)]
+        );
+
+        // Touching the map's edge from outside
+        camera.pos = Point::new(12, 0);
+        assert!(drawn(&MAP, &camera).is_empty());
+        camera.pos = Point::new(-4, -4);
+        assert!(drawn(&MAP, &camera).is_empty());
+    }
+
+    #[test]
+    fn repeats() {
+        let repeating = Tilemap::new(&SHEET, 3, MAP.data).repeating();
+        let mut camera = Camera::new(Size::new(4, 4));
+        camera.pos = Point::new(-4, -4);
+        assert_eq!(
+            drawn(&repeating, &camera),
+            [Call::Contiguous(Rectangle::new(
+                Point::zero(),
+                Size::new(4, 4)
+            ))]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no width")]
+    fn zero_width() {
+        Tilemap::new(&SHEET, 0, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "no tiles")]
+    fn no_tiles() {
+        Tilemap::new(&SHEET, 2, &[]).repeating();
+    }
+
+    #[test]
+    #[should_panic(expected = "whole rows")]
+    fn partial_row() {
+        Tilemap::new(&SHEET, 2, &[1, 2, 1]);
+    }
+}
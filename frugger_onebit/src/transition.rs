@@ -0,0 +1,136 @@
+use crate::dither::{dither, level};
+use embedded_graphics::pixelcolor::BinaryColor;
+use frugger_core::tween::{Ease, Tween};
+
+/// Packed copy of a whole frame, one bit per pixel in the same order as the engine's buffers.
+#[derive(Clone)]
+pub struct BitFrame([u8; 1024]);
+
+impl BitFrame {
+    pub const fn new() -> Self {
+        Self([0; 1024])
+    }
+
+    pub fn from_colors(colors: &[BinaryColor; 8192]) -> Self {
+        let mut frame = Self::new();
+        for (idx, col) in colors.iter().enumerate() {
+            frame.set(idx, *col);
+        }
+        frame
+    }
+
+    pub fn get(&self, idx: usize) -> BinaryColor {
+        (self.0[idx / 8] & (0x80 >> (idx % 8)) != 0).into()
+    }
+
+    pub fn set(&mut self, idx: usize, col: BinaryColor) {
+        if col.is_on() {
+            self.0[idx / 8] |= 0x80 >> (idx % 8);
+        } else {
+            self.0[idx / 8] &= !(0x80 >> (idx % 8));
+        }
+    }
+}
+
+impl Default for BitFrame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How the old frame gives way to the new one.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Transition {
+    /// New frame revealed left to right.
+    Wipe,
+    /// Ordered dither from one to the other.
+    Dissolve,
+    /// Dither the old frame out to black, then the new one in.
+    FadeThroughBlack,
+    /// New frame pushes the old one off to the left.
+    Slide,
+}
+
+/// A transition in progress, owns the frame being transitioned from.
+pub(crate) struct Active {
+    kind: Transition,
+    from: BitFrame,
+    progress: Tween,
+}
+
+impl Active {
+    pub(crate) fn new(kind: Transition, from: BitFrame, frames: u16) -> Self {
+        let ease = match kind {
+            Transition::Slide => Ease::QuadInOut,
+            _ => Ease::Linear,
+        };
+        Self {
+            kind,
+            from,
+            progress: Tween::new(0.0, 1.0, frames, ease),
+        }
+    }
+
+    /// Advance a frame, false once the new frame is fully shown.
+    pub(crate) fn tick(&mut self) -> bool {
+        self.progress.tick();
+        !self.progress.finished()
+    }
+
+    /// Mix the old frame into `next` for the current progress.
+    pub(crate) fn compose(&self, next: &mut [BinaryColor; 8192], width: usize) {
+        let p = self.progress.value();
+        let height = next.len() / width;
+
+        match self.kind {
+            Transition::Wipe => {
+                let edge = (p * width as f32) as usize;
+                for (idx, col) in next.iter_mut().enumerate() {
+                    if idx % width >= edge {
+                        *col = self.from.get(idx);
+                    }
+                }
+            }
+            Transition::Dissolve => {
+                let level = level(p);
+                for (idx, col) in next.iter_mut().enumerate() {
+                    if !dither((idx % width) as i32, (idx / width) as i32, level) {
+                        *col = self.from.get(idx);
+                    }
+                }
+            }
+            Transition::FadeThroughBlack => {
+                let fading_out = p < 0.5;
+                let level = if fading_out {
+                    level(1.0 - p * 2.0)
+                } else {
+                    level(p * 2.0 - 1.0)
+                };
+                for (idx, col) in next.iter_mut().enumerate() {
+                    let src = if fading_out { self.from.get(idx) } else { *col };
+                    *col = if dither((idx % width) as i32, (idx / width) as i32, level) {
+                        src
+                    } else {
+                        BinaryColor::Off
+                    };
+                }
+            }
+            Transition::Slide => {
+                let offset = (p * width as f32) as usize;
+                // Where the new frame's left edge is on screen
+                let edge = width - offset;
+                for y in 0..height {
+                    let row = &mut next[y * width..(y + 1) * width];
+                    // Right to left so the new frame is read before it is overwritten
+                    for x in (0..width).rev() {
+                        row[x] = if x >= edge {
+                            row[x - edge]
+                        } else {
+                            self.from.get(y * width + x + offset)
+                        };
+                    }
+                }
+            }
+        }
+    }
+}
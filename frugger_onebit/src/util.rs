@@ -23,8 +23,11 @@ impl<C> SM<C> {
         }
     }
 
+    /// Replaces the state with the same `id`. Panics if there are already 100 others.
     pub fn add(&mut self, id: usize, state: UpdateFn<C>) {
-        self.states.insert(id, state);
+        self.states
+            .insert(id, state)
+            .expect("state machine is full");
     }
 
     pub fn tick(&mut self, state: &mut C, inputs: &FrugInputs, engine: &mut OneBit) {
@@ -54,12 +57,36 @@ pub fn load_sprite<'a>(bytes: &'a [u8]) -> Spr<'a> {
     Spr::new(bmp)
 }
 
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub enum Flip {
+    #[default]
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+/// Clockwise rotation, applied before flipping.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub enum Rotation {
+    #[default]
+    None,
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+#[derive(Copy, Clone)]
 pub struct Sprite<'a> {
     area: Rectangle,
     data: &'a [u8],
+    mask: Option<&'a [u8]>,
+    flip: Flip,
+    rotation: Rotation,
 }
 
 impl<'a> Sprite<'a> {
+    /// `data` is one bit per pixel, MSB first, with each row padded to a whole byte.
     pub const fn new(
         width: u32,
         height: u32,
@@ -68,7 +95,101 @@ impl<'a> Sprite<'a> {
         data: &'a [u8],
     ) -> Self {
         let area = Rectangle::new(Point::new(anchor_x, anchor_y), Size::new(width, height));
-        Self { area, data }
+        Self {
+            area,
+            data,
+            mask: None,
+            flip: Flip::None,
+            rotation: Rotation::None,
+        }
+    }
+
+    /// Only pixels set in the mask get drawn, same layout as the data.
+    pub const fn with_mask(mut self, mask: &'a [u8]) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub const fn at(mut self, x: i32, y: i32) -> Self {
+        self.area.top_left = Point::new(x, y);
+        self
+    }
+
+    pub const fn flip(mut self, flip: Flip) -> Self {
+        self.flip = flip;
+        self
+    }
+
+    pub const fn rotate(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn size(&self) -> Size {
+        self.area.size
+    }
+
+    /// Draw `region` (relative to the sprite's top left) with its top left at `pos`.
+    pub(crate) fn draw_part<D>(
+        &self,
+        region: Rectangle,
+        pos: Point,
+        flip: Flip,
+        rotation: Rotation,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let Size { width, height } = region.size;
+        let (w, h) = (width as i32, height as i32);
+        let size = match rotation {
+            Rotation::Cw90 | Rotation::Cw270 => Size::new(height, width),
+            Rotation::None | Rotation::Cw180 => region.size,
+        };
+        let area = Rectangle::new(pos, size);
+        let stride = self.area.size.width.div_ceil(8) as i32;
+
+        // Work back from a screen point to the byte and bit of the source pixel
+        let src = move |p: Point| {
+            let (mut x, mut y) = (p.x - pos.x, p.y - pos.y);
+            if matches!(flip, Flip::Horizontal | Flip::Both) {
+                x = size.width as i32 - 1 - x;
+            }
+            if matches!(flip, Flip::Vertical | Flip::Both) {
+                y = size.height as i32 - 1 - y;
+            }
+            let (sx, sy) = match rotation {
+                Rotation::None => (x, y),
+                Rotation::Cw90 => (y, h - 1 - x),
+                Rotation::Cw180 => (w - 1 - x, h - 1 - y),
+                Rotation::Cw270 => (w - 1 - y, x),
+            };
+            let (x, y) = (region.top_left.x + sx, region.top_left.y + sy);
+            ((y * stride + x / 8) as usize, 0x80u8 >> (x % 8))
+        };
+        let col = |(idx, bit): (usize, u8)| (self.data[idx] & bit != 0).into();
+
+        match self.mask {
+            None => target.fill_contiguous(&area, area.points().map(|p| col(src(p)))),
+            Some(mask) => target.draw_iter(area.points().filter_map(|p| {
+                let (idx, bit) = src(p);
+                (mask[idx] & bit != 0).then(|| Pixel(p, col((idx, bit))))
+            })),
+        }
+    }
+}
+
+impl Transform for Sprite<'_> {
+    fn translate(&self, by: Point) -> Self {
+        let mut sprite = *self;
+        sprite.area.top_left += by;
+        sprite
+    }
+
+    fn translate_mut(&mut self, by: Point) -> &mut Self {
+        self.area.top_left += by;
+        self
     }
 }
 
@@ -80,15 +201,115 @@ impl Drawable for Sprite<'_> {
     where
         D: DrawTarget<Color = Self::Color>,
     {
-        target.fill_contiguous(
-            &self.area,
-            self.data.iter().map(|x| {
-                if *x == 0 {
-                    BinaryColor::Off
-                } else {
-                    BinaryColor::On
-                }
-            }),
+        self.draw_part(
+            Rectangle::new(Point::zero(), self.area.size),
+            self.area.top_left,
+            self.flip,
+            self.rotation,
+            target,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frugger_core::mock::Recorder;
+    use frugger_core::Orientation;
+    use std::string::String;
+    use std::vec::Vec;
+
+    /// 3x2, no two ways of turning it look the same.
+    const SPRITE: Sprite = Sprite::new(3, 2, 0, 0, &[0b1100_0000, 0b0010_0000]);
+
+    /// What `sprite` draws over a 4x4 of `bg`, a row at a time.
+    fn drawn(sprite: Sprite, bg: BinaryColor) -> Vec<String> {
+        let mut target = Recorder::new(Size::new(4, 4), bg);
+        sprite.draw(&mut target).unwrap();
+        target
+            .pixels()
+            .chunks(4)
+            .map(|row| {
+                row.iter()
+                    .map(|c| if c.is_on() { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flips() {
+        let off = BinaryColor::Off;
+        assert_eq!(drawn(SPRITE, off), ["##..", "..#.", "....", "...."]);
+        let flipped = |flip| drawn(SPRITE.flip(flip), off);
+        assert_eq!(flipped(Flip::Horizontal), [".##.", "#...", "....", "...."]);
+        assert_eq!(flipped(Flip::Vertical), ["..#.", "##..", "....", "...."]);
+        assert_eq!(flipped(Flip::Both), ["#...", ".##.", "....", "...."]);
+    }
+
+    #[test]
+    fn rotations() {
+        let off = BinaryColor::Off;
+        let rotated = |rotation| drawn(SPRITE.rotate(rotation), off);
+        assert_eq!(rotated(Rotation::Cw90), [".#..", ".#..", "#...", "...."]);
+        assert_eq!(rotated(Rotation::Cw180), ["#...", ".##.", "....", "...."]);
+        assert_eq!(rotated(Rotation::Cw270), [".#..", "#...", "#...", "...."]);
+
+        // Turned, then flipped
+        let both = SPRITE.rotate(Rotation::Cw90).flip(Flip::Horizontal);
+        assert_eq!(drawn(both, off), ["#...", "#...", ".#..", "...."]);
+    }
+
+    #[test]
+    fn mask() {
+        // All Off, only drawn where the mask is set
+        let sprite = Sprite::new(3, 2, 0, 0, &[0, 0]).with_mask(&[0b1010_0000, 0b0100_0000]);
+        assert_eq!(
+            drawn(sprite, BinaryColor::On),
+            [".#.#", "#.##", "####", "####"]
+        );
+        let turned = sprite.rotate(Rotation::Cw90);
+        assert_eq!(
+            drawn(turned, BinaryColor::On),
+            ["#.##", ".###", "#.##", "####"]
+        );
+    }
+
+    fn next(state: &mut usize, _: &FrugInputs, _: &mut OneBit) -> usize {
+        *state += 1;
+        *state
+    }
+
+    fn full() -> SM<usize> {
+        let mut sm = SM::new();
+        for id in 0..100 {
+            sm.add(id, next);
+        }
+        sm
+    }
+
+    #[test]
+    fn every_state() {
+        let mut sm = full();
+        let mut engine = OneBit::new(Orientation::Portrait);
+        let mut count = 0;
+        for _ in 0..150 {
+            sm.tick(&mut count, &FrugInputs::default(), &mut engine);
+        }
+        // Stuck once it leaves the states it has
+        assert_eq!(count, 100);
+        assert_eq!(sm.curr, 100);
+
+        // Same id replaces
+        sm.add(99, |_, _, _| 0);
+        sm.curr = 99;
+        sm.tick(&mut count, &FrugInputs::default(), &mut engine);
+        assert_eq!(sm.curr, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "state machine is full")]
+    fn too_many_states() {
+        full().add(100, next);
+    }
+}
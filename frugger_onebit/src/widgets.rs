@@ -0,0 +1,962 @@
+//! Small widgets for menus and dialogs. They keep only the state they need between frames, take
+//! the area to lay themselves out in when drawing and read `FrugInputs` in `update`.
+
+use core::fmt::Write;
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+use frugger_core::tween::{Ease, Sequence, Step, Tween};
+use frugger_core::FrugInputs;
+
+/// Text at `align` across `area`, centred vertically.
+fn draw_text<D>(
+    text: &str,
+    font: &MonoFont,
+    color: BinaryColor,
+    align: Alignment,
+    area: &Rectangle,
+    target: &mut D,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let middle = Point::new(
+        area.top_left.x + area.size.width as i32 / 2,
+        area.center().y,
+    );
+    let x = match align {
+        Alignment::Left => area.top_left.x,
+        Alignment::Center => middle.x,
+        Alignment::Right => area.top_left.x + area.size.width as i32 - 1,
+    };
+    let style = TextStyleBuilder::new()
+        .alignment(align)
+        .baseline(Baseline::Middle)
+        .build();
+    Text::with_text_style(
+        text,
+        Point::new(x, middle.y),
+        MonoTextStyle::new(font, color),
+        style,
+    )
+    .draw(target)?;
+    Ok(())
+}
+
+/// Previous and next for vertical lists and horizontal values, whichever way the buttons are held.
+fn nav(inputs: &FrugInputs) -> i32 {
+    if inputs.left.pressed() || inputs.up.pressed() {
+        -1
+    } else if inputs.right.pressed() || inputs.down.pressed() {
+        1
+    } else {
+        0
+    }
+}
+
+pub struct Label<'a> {
+    text: &'a str,
+    font: &'a MonoFont<'a>,
+    align: Alignment,
+}
+
+impl<'a> Label<'a> {
+    /// Centred.
+    pub const fn new(text: &'a str, font: &'a MonoFont<'a>) -> Self {
+        Self {
+            text,
+            font,
+            align: Alignment::Center,
+        }
+    }
+
+    pub const fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn draw<D>(&self, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        draw_text(
+            self.text,
+            self.font,
+            BinaryColor::On,
+            self.align,
+            area,
+            target,
+        )
+    }
+}
+
+/// Vertical list with a cursor that slides between entries. Scrolls if the items don't fit.
+pub struct List<'a> {
+    items: &'a [&'a str],
+    font: &'a MonoFont<'a>,
+    selected: usize,
+    /// Cursor position in rows.
+    cursor: Tween,
+}
+
+impl<'a> List<'a> {
+    pub fn new(items: &'a [&'a str], font: &'a MonoFont<'a>) -> Self {
+        Self {
+            items,
+            font,
+            selected: 0,
+            cursor: Tween::idle(0.0),
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select(&mut self, idx: usize) {
+        self.selected = idx.min(self.items.len().saturating_sub(1));
+        self.cursor = Tween::idle(self.selected as f32);
+    }
+
+    /// Move with the direction buttons, wrapping at the ends. Returns the item picked with A.
+    pub fn update(&mut self, inputs: &FrugInputs) -> Option<usize> {
+        let len = self.items.len() as i32;
+        let step = nav(inputs);
+        if step != 0 && len > 0 {
+            self.selected = (self.selected as i32 + step).rem_euclid(len) as usize;
+            self.cursor.retarget(self.selected as f32, 8, Ease::QuadOut);
+        }
+        self.cursor.tick();
+
+        (inputs.a.pressed() && len > 0).then_some(self.selected)
+    }
+
+    fn row_height(&self) -> u32 {
+        self.font.character_size.height + 2
+    }
+
+    /// First row shown, keeps the selection on screen.
+    fn first_row(&self, area: &Rectangle) -> usize {
+        let rows = (area.size.height / self.row_height()).max(1) as usize;
+        self.selected.saturating_sub(rows - 1)
+    }
+
+    fn row(&self, area: &Rectangle, row: f32) -> Rectangle {
+        let y = area.top_left.y + libm::roundf(row * self.row_height() as f32) as i32;
+        Rectangle::new(
+            Point::new(area.top_left.x, y),
+            Size::new(area.size.width, self.row_height()),
+        )
+    }
+
+    /// Just the entries, for drawing once into a background when they can't scroll.
+    pub fn draw_items<D>(&self, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let first = self.first_row(area);
+        for (row, item) in self.items.iter().enumerate().skip(first) {
+            if (row - first + 1) as u32 * self.row_height() > area.size.height {
+                break;
+            }
+            let rect = self.row(area, (row - first) as f32);
+            draw_text(
+                item,
+                self.font,
+                BinaryColor::On,
+                Alignment::Center,
+                &rect,
+                target,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Outline around the selection, a pixel outside its row.
+    pub fn draw_cursor<D>(&self, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let first = self.first_row(area) as f32;
+        let row = self.row(area, self.cursor.value() - first);
+        Rectangle::new(row.top_left - Point::new(0, 1), row.size + Size::new(0, 2))
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(target)
+    }
+
+    pub fn draw<D>(&self, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        self.draw_items(area, target)?;
+        self.draw_cursor(area, target)
+    }
+}
+
+/// Number picked with left and right, label on the left and value on the right.
+pub struct Spinner<'a> {
+    label: &'a str,
+    font: &'a MonoFont<'a>,
+    value: i32,
+    min: i32,
+    max: i32,
+    step: i32,
+}
+
+impl<'a> Spinner<'a> {
+    pub const fn new(label: &'a str, font: &'a MonoFont<'a>, min: i32, max: i32) -> Self {
+        Self {
+            label,
+            font,
+            value: min,
+            min,
+            max,
+            step: 1,
+        }
+    }
+
+    pub const fn step(mut self, step: i32) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: i32) {
+        self.value = value.clamp(self.min, self.max);
+    }
+
+    /// True if the value changed.
+    pub fn update(&mut self, inputs: &FrugInputs) -> bool {
+        let old = self.value;
+        self.set_value(
+            self.value
+                .saturating_add(nav(inputs).saturating_mul(self.step)),
+        );
+        old != self.value
+    }
+
+    /// Arrows show which way the value can still go.
+    pub fn draw<D>(&self, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        draw_text(
+            self.label,
+            self.font,
+            BinaryColor::On,
+            Alignment::Left,
+            area,
+            target,
+        )?;
+
+        let mut text = heapless::String::<16>::new();
+        let less = if self.value > self.min { '<' } else { ' ' };
+        let more = if self.value < self.max { '>' } else { ' ' };
+        write!(text, "{less}{}{more}", self.value).unwrap();
+        draw_text(
+            &text,
+            self.font,
+            BinaryColor::On,
+            Alignment::Right,
+            area,
+            target,
+        )
+    }
+}
+
+/// On/off switch, flipped with A.
+pub struct Toggle<'a> {
+    label: &'a str,
+    font: &'a MonoFont<'a>,
+    on: bool,
+}
+
+impl<'a> Toggle<'a> {
+    pub const fn new(label: &'a str, font: &'a MonoFont<'a>, on: bool) -> Self {
+        Self { label, font, on }
+    }
+
+    pub fn on(&self) -> bool {
+        self.on
+    }
+
+    /// True if it was flipped.
+    pub fn update(&mut self, inputs: &FrugInputs) -> bool {
+        if inputs.a.pressed() {
+            self.on = !self.on;
+        }
+        inputs.a.pressed()
+    }
+
+    /// Label on the left, a box on the right that is filled when on.
+    pub fn draw<D>(&self, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        draw_text(
+            self.label,
+            self.font,
+            BinaryColor::On,
+            Alignment::Left,
+            area,
+            target,
+        )?;
+
+        let side = self
+            .font
+            .character_size
+            .height
+            .min(area.size.height)
+            .saturating_sub(2);
+        let top_left = Point::new(
+            area.top_left.x + area.size.width as i32 - side as i32,
+            area.center().y - side as i32 / 2,
+        );
+        let style = if self.on {
+            PrimitiveStyle::with_fill(BinaryColor::On)
+        } else {
+            PrimitiveStyle::with_stroke(BinaryColor::On, 1)
+        };
+        Rectangle::new(top_left, Size::new_equal(side))
+            .into_styled(style)
+            .draw(target)
+    }
+}
+
+/// Outlined bar filled `fraction` of the way.
+pub struct ProgressBar;
+
+impl ProgressBar {
+    pub fn draw<D>(fraction: f32, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        area.into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(target)?;
+
+        let inner = area.offset(-2);
+        let width = (inner.size.width as f32 * fraction.clamp(0.0, 1.0)) as u32;
+        target.fill_solid(
+            &Rectangle::new(inner.top_left, Size::new(width, inner.size.height)),
+            BinaryColor::On,
+        )
+    }
+}
+
+/// Yes/no question drawn over whatever is underneath. Starts on no.
+pub struct Confirm<'a> {
+    message: &'a str,
+    font: &'a MonoFont<'a>,
+    yes: bool,
+}
+
+impl<'a> Confirm<'a> {
+    pub const fn new(message: &'a str, font: &'a MonoFont<'a>) -> Self {
+        Self {
+            message,
+            font,
+            yes: false,
+        }
+    }
+
+    /// Left and right pick, A answers and B is always no.
+    pub fn update(&mut self, inputs: &FrugInputs) -> Option<bool> {
+        if inputs.left.pressed() || inputs.right.pressed() {
+            self.yes = !self.yes;
+        }
+
+        if inputs.a.pressed() {
+            Some(self.yes)
+        } else if inputs.b.pressed() {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw<D>(&self, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        target.fill_solid(area, BinaryColor::Off)?;
+        area.into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(target)?;
+
+        let button_h = self.font.character_size.height + 2;
+        let inner = area.offset(-2);
+        let message = Rectangle::new(
+            inner.top_left,
+            Size::new(inner.size.width, inner.size.height.saturating_sub(button_h)),
+        );
+        draw_text(
+            self.message,
+            self.font,
+            BinaryColor::On,
+            Alignment::Center,
+            &message,
+            target,
+        )?;
+
+        let half = inner.size.width / 2;
+        let y = inner.top_left.y + inner.size.height as i32 - button_h as i32;
+        for (text, x, selected) in [
+            ("YES", inner.top_left.x, self.yes),
+            ("NO", inner.top_left.x + half as i32, !self.yes),
+        ] {
+            let button = Rectangle::new(Point::new(x, y), Size::new(half, button_h));
+            let color = if selected {
+                target.fill_solid(&button, BinaryColor::On)?;
+                BinaryColor::Off
+            } else {
+                BinaryColor::On
+            };
+            draw_text(text, self.font, color, Alignment::Center, &button, target)?;
+        }
+        Ok(())
+    }
+}
+
+/// Character pages for `TextEntry`, switched with up and down.
+pub const PAGES: [&str; 3] = ["ABCDEFGHIJKLMNOPQRSTUVWXYZ", "0123456789", " .,-_!?#&*+/:"];
+
+/// Text typed a character at a time: left and right pick from the page, A adds it and B deletes
+/// the last one. After the page comes a done marker, A on it finishes.
+pub struct TextEntry<'a, const N: usize> {
+    font: &'a MonoFont<'a>,
+    text: heapless::String<N>,
+    /// Shortest text that can be finished.
+    min_len: usize,
+    page: usize,
+    /// Position in the page, one past the end is done.
+    pick: usize,
+    /// Cursor visibility, shown while above half.
+    blink: Sequence<'static>,
+}
+
+impl<'a, const N: usize> TextEntry<'a, N> {
+    /// Snap off, wait, snap back on, wait.
+    const BLINK: [Step; 4] = [
+        Step::hold(1.0, 15),
+        Step::new(0.0, 1, Ease::Linear),
+        Step::hold(0.0, 14),
+        Step::new(1.0, 1, Ease::Linear),
+    ];
+
+    /// Needs at least one character to finish.
+    pub fn new(font: &'a MonoFont<'a>) -> Self {
+        Self {
+            font,
+            text: heapless::String::new(),
+            min_len: 1,
+            page: 0,
+            pick: 0,
+            blink: Sequence::new(1.0, &Self::BLINK).looping(),
+        }
+    }
+
+    /// No more than `N`, or it could never be finished.
+    pub fn min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len.min(N);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Anything past `N` is cut off.
+    pub fn set_text(&mut self, text: &str) {
+        self.text.clear();
+        for c in text.chars() {
+            if self.text.push(c).is_err() {
+                break;
+            }
+        }
+        self.clamp_pick();
+    }
+
+    fn full(&self) -> bool {
+        self.text.len() == N
+    }
+
+    /// Characters to pick from plus the done marker, if it can be picked.
+    fn choices(&self) -> usize {
+        let chars = if self.full() {
+            0
+        } else {
+            PAGES[self.page].len()
+        };
+        chars + usize::from(self.text.len() >= self.min_len)
+    }
+
+    /// Character under the cursor, none for the done marker.
+    fn current(&self) -> Option<char> {
+        if self.full() {
+            return None;
+        }
+        PAGES[self.page]
+            .as_bytes()
+            .get(self.pick)
+            .map(|b| *b as char)
+    }
+
+    fn clamp_pick(&mut self) {
+        self.pick = self.pick.min(self.choices().saturating_sub(1));
+    }
+
+    /// True once the done marker is picked.
+    pub fn update(&mut self, inputs: &FrugInputs) -> bool {
+        self.blink.tick();
+
+        let before = (self.page, self.pick, self.text.len());
+
+        if inputs.up.pressed() {
+            self.page = (self.page + PAGES.len() - 1) % PAGES.len();
+            self.pick = 0;
+        } else if inputs.down.pressed() {
+            self.page = (self.page + 1) % PAGES.len();
+            self.pick = 0;
+        }
+
+        let choices = self.choices().max(1) as i32;
+        if inputs.left.pressed() {
+            self.pick = (self.pick as i32 - 1).rem_euclid(choices) as usize;
+        } else if inputs.right.pressed() {
+            self.pick = (self.pick as i32 + 1).rem_euclid(choices) as usize;
+        }
+
+        if inputs.b.pressed() {
+            self.text.pop();
+        }
+
+        let mut done = false;
+        if inputs.a.pressed() {
+            match self.current() {
+                Some(c) => {
+                    let _ = self.text.push(c);
+                }
+                None => done = self.text.len() >= self.min_len,
+            }
+        }
+        self.clamp_pick();
+
+        // Show the cursor straight away after any change
+        if before != (self.page, self.pick, self.text.len()) {
+            self.blink.restart();
+        }
+        done
+    }
+
+    /// Text from the left of `area`, then the blinking pick. The done marker is a filled cell.
+    pub fn draw<D>(&self, area: &Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        draw_text(
+            &self.text,
+            self.font,
+            BinaryColor::On,
+            Alignment::Left,
+            area,
+            target,
+        )?;
+
+        if self.blink.value() < 0.5 {
+            return Ok(());
+        }
+
+        let glyph = self.font.character_size;
+        let advance = (glyph.width + self.font.character_spacing) as i32;
+        let cell = Rectangle::new(
+            Point::new(
+                area.top_left.x + self.text.len() as i32 * advance,
+                area.center().y - (glyph.height as i32 - 1) / 2,
+            ),
+            glyph,
+        );
+
+        match self.current() {
+            Some(c) => {
+                let mut buf = [0; 4];
+                draw_text(
+                    c.encode_utf8(&mut buf),
+                    self.font,
+                    BinaryColor::On,
+                    Alignment::Left,
+                    &cell,
+                    target,
+                )
+            }
+            None => target.fill_solid(&cell.offset(-1), BinaryColor::On),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mono_font::ascii::FONT_6X10;
+    use frugger_core::mock::Recorder;
+    use frugger_core::ButtonState;
+
+    fn target() -> Recorder<BinaryColor> {
+        Recorder::new(Size::new(64, 40), BinaryColor::Off)
+    }
+
+    type Button = fn(&mut FrugInputs) -> &mut ButtonState;
+
+    fn pressed(button: Button) -> FrugInputs {
+        let mut inputs = FrugInputs::default();
+        *button(&mut inputs) = ButtonState::PRESSED;
+        inputs
+    }
+
+    fn lit(target: &Recorder<BinaryColor>, x: i32, y: i32) -> bool {
+        target.get(Point::new(x, y)) == Some(BinaryColor::On)
+    }
+
+    /// Two rows of `FONT_6X10`, a pixel down so the cursor outline fits.
+    const AREA: Rectangle = Rectangle::new(Point::new(0, 2), Size::new(64, 24));
+
+    fn row(idx: i32) -> Rectangle {
+        Rectangle::new(Point::new(0, 2 + idx * 12), Size::new(64, 12))
+    }
+
+    #[test]
+    fn list_navigation() {
+        let mut list = List::new(&["A", "B", "C"], &FONT_6X10);
+        assert_eq!(list.update(&pressed(|i| &mut i.down)), None);
+        assert_eq!(list.selected(), 1);
+        list.update(&pressed(|i| &mut i.up));
+        list.update(&pressed(|i| &mut i.left));
+        assert_eq!(list.selected(), 2, "wraps");
+        list.update(&pressed(|i| &mut i.right));
+        assert_eq!(list.selected(), 0, "wraps");
+
+        // Held doesn't repeat
+        let held = FrugInputs {
+            down: ButtonState::DOWN,
+            ..Default::default()
+        };
+        list.update(&held);
+        assert_eq!(list.selected(), 0);
+
+        list.select(9);
+        assert_eq!(list.selected(), 2);
+        assert_eq!(list.update(&pressed(|i| &mut i.a)), Some(2));
+
+        let mut empty = List::new(&[], &FONT_6X10);
+        assert_eq!(empty.update(&pressed(|i| &mut i.down)), None);
+        assert_eq!(empty.update(&pressed(|i| &mut i.a)), None);
+    }
+
+    #[test]
+    fn list_layout() {
+        let mut list = List::new(&["A", "B", "C"], &FONT_6X10);
+        list.update(&pressed(|i| &mut i.down));
+        for _ in 0..8 {
+            list.update(&FrugInputs::default());
+        }
+        let mut drawn = target();
+        list.draw(&AREA, &mut drawn).unwrap();
+
+        let mut expected = target();
+        for (text, idx) in [("A", 0), ("B", 1)] {
+            Label::new(text, &FONT_6X10)
+                .draw(&row(idx), &mut expected)
+                .unwrap();
+        }
+        // Cursor a pixel outside the second row
+        Rectangle::new(Point::new(0, 13), Size::new(64, 14))
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(&mut expected)
+            .unwrap();
+        assert_eq!(drawn.pixels(), expected.pixels());
+    }
+
+    #[test]
+    fn list_scrolls() {
+        let mut list = List::new(&["A", "B", "C"], &FONT_6X10);
+        list.select(2);
+        let mut drawn = target();
+        list.draw_items(&AREA, &mut drawn).unwrap();
+
+        let mut expected = target();
+        for (text, idx) in [("B", 0), ("C", 1)] {
+            Label::new(text, &FONT_6X10)
+                .draw(&row(idx), &mut expected)
+                .unwrap();
+        }
+        assert_eq!(drawn.pixels(), expected.pixels());
+
+        // The cursor stays on the last row
+        let mut cursor = target();
+        list.draw_cursor(&AREA, &mut cursor).unwrap();
+        assert!(lit(&cursor, 0, 13) && lit(&cursor, 0, 26));
+        assert!(!lit(&cursor, 0, 1));
+    }
+
+    #[test]
+    fn spinner_navigation() {
+        let mut spinner = Spinner::new("N", &FONT_6X10, 0, 10).step(3);
+        assert!(!spinner.update(&pressed(|i| &mut i.left)), "already at min");
+        assert!(spinner.update(&pressed(|i| &mut i.right)));
+        assert!(spinner.update(&pressed(|i| &mut i.down)));
+        assert!(spinner.update(&pressed(|i| &mut i.right)));
+        assert_eq!(spinner.value(), 9);
+        assert!(spinner.update(&pressed(|i| &mut i.right)));
+        assert_eq!(spinner.value(), 10, "clamped");
+        assert!(!spinner.update(&pressed(|i| &mut i.right)));
+        assert!(spinner.update(&pressed(|i| &mut i.up)));
+        assert_eq!(spinner.value(), 7);
+
+        spinner.set_value(-5);
+        assert_eq!(spinner.value(), 0);
+
+        // Steps past the ends of i32
+        let mut wide = Spinner::new("N", &FONT_6X10, i32::MIN, i32::MAX).step(i32::MAX);
+        wide.update(&pressed(|i| &mut i.left));
+        wide.update(&pressed(|i| &mut i.left));
+        assert_eq!(wide.value(), i32::MIN);
+        wide.set_value(i32::MAX - 1);
+        wide.update(&pressed(|i| &mut i.right));
+        assert_eq!(wide.value(), i32::MAX);
+    }
+
+    #[test]
+    fn spinner_layout() {
+        let mut spinner = Spinner::new("LIVES", &FONT_6X10, 1, 5);
+        for (value, text) in [(1, " 1>"), (3, "<3>"), (5, "<5 ")] {
+            spinner.set_value(value);
+            let mut drawn = target();
+            spinner.draw(&row(0), &mut drawn).unwrap();
+
+            let mut expected = target();
+            Label::new("LIVES", &FONT_6X10)
+                .align(Alignment::Left)
+                .draw(&row(0), &mut expected)
+                .unwrap();
+            Label::new(text, &FONT_6X10)
+                .align(Alignment::Right)
+                .draw(&row(0), &mut expected)
+                .unwrap();
+            assert_eq!(drawn.pixels(), expected.pixels(), "{value}");
+        }
+    }
+
+    #[test]
+    fn toggle() {
+        let mut toggle = Toggle::new("SOUND", &FONT_6X10, false);
+        assert!(!toggle.update(&pressed(|i| &mut i.b)));
+        assert!(toggle.update(&pressed(|i| &mut i.a)));
+        assert!(toggle.on());
+
+        // The box is 8 pixels against the right edge, filled when on
+        let mut drawn = target();
+        toggle.draw(&row(0), &mut drawn).unwrap();
+        assert!(lit(&drawn, 59, 7) && lit(&drawn, 63, 4));
+        assert!(!lit(&drawn, 55, 7));
+
+        toggle.update(&pressed(|i| &mut i.a));
+        let mut drawn = target();
+        toggle.draw(&row(0), &mut drawn).unwrap();
+        assert!(!lit(&drawn, 59, 7) && lit(&drawn, 63, 4));
+
+        // Too short for a box
+        let mut drawn = target();
+        let thin = Rectangle::new(Point::zero(), Size::new(64, 1));
+        toggle.draw(&thin, &mut drawn).unwrap();
+    }
+
+    #[test]
+    fn progress_bar() {
+        let area = Rectangle::new(Point::zero(), Size::new(20, 6));
+        for (fraction, filled) in [(0.5, 8), (-1.0, 0), (2.0, 16)] {
+            let mut drawn = target();
+            ProgressBar::draw(fraction, &area, &mut drawn).unwrap();
+
+            let mut expected = target();
+            area.into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+                .draw(&mut expected)
+                .unwrap();
+            let inner = Rectangle::new(Point::new(2, 2), Size::new(filled, 2));
+            expected.fill_solid(&inner, BinaryColor::On).unwrap();
+            assert_eq!(drawn.pixels(), expected.pixels(), "{fraction}");
+        }
+    }
+
+    #[test]
+    fn confirm() {
+        let mut confirm = Confirm::new("SURE?", &FONT_6X10);
+        assert_eq!(confirm.update(&FrugInputs::default()), None);
+        assert_eq!(confirm.update(&pressed(|i| &mut i.a)), Some(false));
+        assert_eq!(confirm.update(&pressed(|i| &mut i.right)), None);
+        assert_eq!(confirm.update(&pressed(|i| &mut i.a)), Some(true));
+        assert_eq!(confirm.update(&pressed(|i| &mut i.b)), Some(false));
+        confirm.update(&pressed(|i| &mut i.left));
+        assert_eq!(confirm.update(&pressed(|i| &mut i.a)), Some(false));
+    }
+
+    #[test]
+    fn confirm_layout() {
+        // Buttons along the bottom inside the border, the picked one filled
+        let area = Rectangle::new(Point::zero(), Size::new(64, 40));
+        let mut confirm = Confirm::new("SURE?", &FONT_6X10);
+        let mut drawn = target();
+        confirm.draw(&area, &mut drawn).unwrap();
+        assert!(lit(&drawn, 0, 0) && lit(&drawn, 63, 39));
+        assert!(!lit(&drawn, 2, 26) && lit(&drawn, 32, 26));
+
+        confirm.update(&pressed(|i| &mut i.left));
+        let mut drawn = target();
+        confirm.draw(&area, &mut drawn).unwrap();
+        assert!(lit(&drawn, 2, 26) && !lit(&drawn, 32, 26));
+    }
+
+    fn new_entry() -> TextEntry<'static, 3> {
+        TextEntry::new(&FONT_6X10)
+    }
+
+    #[test]
+    fn text_entry_pages() {
+        let mut entry = new_entry();
+        assert_eq!(entry.current(), Some('A'));
+        entry.update(&pressed(|i| &mut i.down));
+        assert_eq!(entry.current(), Some('0'));
+        entry.update(&pressed(|i| &mut i.right));
+        assert_eq!(entry.current(), Some('1'));
+
+        // Back to the start of the page each time
+        entry.update(&pressed(|i| &mut i.down));
+        assert_eq!(entry.current(), Some(' '));
+        entry.update(&pressed(|i| &mut i.down));
+        assert_eq!(entry.current(), Some('A'), "wraps");
+        entry.update(&pressed(|i| &mut i.up));
+        assert_eq!(entry.current(), Some(' '), "wraps");
+    }
+
+    #[test]
+    fn text_entry_done_marker() {
+        // Nothing typed, so no done marker to wrap onto
+        let mut entry = new_entry();
+        entry.update(&pressed(|i| &mut i.left));
+        assert_eq!(entry.current(), Some('Z'));
+        entry.update(&pressed(|i| &mut i.right));
+        assert_eq!(entry.current(), Some('A'));
+
+        assert!(!entry.update(&pressed(|i| &mut i.a)));
+        assert_eq!(entry.text(), "A");
+        entry.update(&pressed(|i| &mut i.left));
+        assert_eq!(entry.current(), None);
+        entry.update(&pressed(|i| &mut i.right));
+        assert_eq!(entry.current(), Some('A'), "wraps");
+        entry.update(&pressed(|i| &mut i.left));
+        assert!(entry.update(&pressed(|i| &mut i.a)));
+        assert_eq!(entry.text(), "A");
+    }
+
+    #[test]
+    fn text_entry_delete() {
+        let mut entry = new_entry();
+        entry.set_text("AB");
+        entry.update(&pressed(|i| &mut i.b));
+        assert_eq!(entry.text(), "A");
+
+        // Deleting the last one takes the done marker with it
+        entry.update(&pressed(|i| &mut i.left));
+        assert_eq!(entry.current(), None);
+        entry.update(&pressed(|i| &mut i.b));
+        assert_eq!(entry.text(), "");
+        assert_eq!(entry.current(), Some('Z'));
+
+        entry.update(&pressed(|i| &mut i.b));
+        assert_eq!(entry.text(), "");
+    }
+
+    #[test]
+    fn text_entry_min_len() {
+        let mut entry = new_entry().min_len(2);
+        entry.update(&pressed(|i| &mut i.a));
+        // Too short to finish, left wraps past where the done marker goes
+        entry.update(&pressed(|i| &mut i.left));
+        assert_eq!(entry.current(), Some('Z'));
+        assert!(!entry.update(&pressed(|i| &mut i.a)));
+        assert_eq!(entry.text(), "AZ");
+        entry.update(&pressed(|i| &mut i.right));
+        assert_eq!(entry.current(), None);
+        assert!(entry.update(&pressed(|i| &mut i.a)));
+
+        // Anything typed finishes
+        let mut entry = new_entry().min_len(0);
+        entry.update(&pressed(|i| &mut i.left));
+        assert_eq!(entry.current(), None);
+        assert!(entry.update(&pressed(|i| &mut i.a)));
+        assert_eq!(entry.text(), "");
+    }
+
+    #[test]
+    fn text_entry_full() {
+        let mut entry = new_entry();
+        entry.set_text("ABCD");
+        assert_eq!(entry.text(), "ABC");
+        assert_eq!(entry.current(), None);
+        let buttons: [Button; 4] = [
+            |i| &mut i.left,
+            |i| &mut i.right,
+            |i| &mut i.up,
+            |i| &mut i.down,
+        ];
+        for button in buttons {
+            entry.update(&pressed(button));
+            assert_eq!(entry.current(), None);
+        }
+
+        entry.update(&pressed(|i| &mut i.b));
+        assert_eq!(entry.current(), Some('A'));
+        entry.update(&pressed(|i| &mut i.a));
+        assert_eq!(entry.text(), "ABA");
+        assert!(entry.update(&pressed(|i| &mut i.a)));
+    }
+
+    #[test]
+    fn text_entry_min_len_past_n() {
+        // Would need more than fits, finishing when full instead
+        let mut entry = new_entry().min_len(5);
+        entry.set_text("ABC");
+        assert_eq!(entry.current(), None);
+        assert!(entry.update(&pressed(|i| &mut i.a)));
+    }
+
+    #[test]
+    fn text_entry_blink() {
+        let mut entry = new_entry();
+        let idle = |entry: &mut TextEntry<3>| {
+            for _ in 0..20 {
+                entry.update(&FrugInputs::default());
+            }
+            assert!(entry.blink.value() < 0.5);
+        };
+
+        let buttons: [Button; 4] = [
+            |i| &mut i.right,
+            |i| &mut i.down,
+            |i| &mut i.a,
+            |i| &mut i.b,
+        ];
+        for button in buttons {
+            idle(&mut entry);
+            entry.update(&pressed(button));
+            assert!(entry.blink.value() >= 0.5);
+        }
+
+        // Nothing changed, still off
+        idle(&mut entry);
+        entry.update(&pressed(|i| &mut i.b));
+        assert!(entry.blink.value() < 0.5);
+    }
+}
@@ -0,0 +1,174 @@
+//! Shared by the integration tests: a display that keeps what was drawn and scripted buttons.
+
+// Each test file uses a different part of this
+#![allow(dead_code)]
+
+use core::convert::Infallible;
+use std::fmt::Write;
+
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use frugger_core::{ButtonState, FrugInputs, FruggerEngine, FruggerGame};
+use frugger_onebit::OneBit;
+
+pub const WIDTH: usize = 64;
+pub const HEIGHT: usize = 128;
+
+/// The portrait screen, pixels stay until drawn over like on the real display.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Screen {
+    pixels: Vec<bool>,
+}
+
+impl Screen {
+    pub fn new() -> Self {
+        Self {
+            pixels: vec![false; WIDTH * HEIGHT],
+        }
+    }
+
+    pub fn on(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * WIDTH + x]
+    }
+
+    pub fn lit(&self) -> usize {
+        self.pixels.iter().filter(|on| **on).count()
+    }
+
+    /// Plain PBM, `1` is On. Viewers show On as black.
+    pub fn to_pbm(&self) -> String {
+        let mut pbm = format!("P1\n{WIDTH} {HEIGHT}\n");
+        for row in self.pixels.chunks(WIDTH) {
+            row.iter()
+                .for_each(|on| pbm.push(if *on { '1' } else { '0' }));
+            pbm.push('\n');
+        }
+        pbm
+    }
+
+    /// Reads what `to_pbm` writes, `None` if it is some other PBM.
+    pub fn from_pbm(pbm: &str) -> Option<Self> {
+        let mut lines = pbm.lines();
+        if lines.next()? != "P1" || lines.next()? != format!("{WIDTH} {HEIGHT}") {
+            return None;
+        }
+        let pixels: Vec<bool> = lines
+            .flat_map(str::chars)
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c == '1')
+            .collect();
+        (pixels.len() == WIDTH * HEIGHT).then_some(Self { pixels })
+    }
+
+    /// Rows that differ, `expected` then `self`, `#` for On.
+    pub fn diff(&self, expected: &Screen) -> String {
+        let row = |screen: &Screen, y: usize| -> String {
+            (0..WIDTH)
+                .map(|x| if screen.on(x, y) { '#' } else { '.' })
+                .collect()
+        };
+        let mut out = String::new();
+        for y in (0..HEIGHT).filter(|y| row(self, *y) != row(expected, *y)) {
+            writeln!(out, "{y:3} {}  {}", row(expected, y), row(self, y)).unwrap();
+        }
+        out
+    }
+}
+
+impl Default for Screen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dimensions for Screen {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), Size::new(WIDTH as u32, HEIGHT as u32))
+    }
+}
+
+impl DrawTarget for Screen {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, col) in pixels {
+            if (0..WIDTH as i32).contains(&p.x) && (0..HEIGHT as i32).contains(&p.y) {
+                self.pixels[p.y as usize * WIDTH + p.x as usize] = col.is_on();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Buttons held down, or'd together.
+pub const NONE: u8 = 0;
+pub const A: u8 = 1;
+pub const B: u8 = 1 << 1;
+pub const LEFT: u8 = 1 << 2;
+pub const RIGHT: u8 = 1 << 3;
+pub const UP: u8 = 1 << 4;
+pub const DOWN: u8 = 1 << 5;
+
+/// Turns the buttons held each frame into presses and releases, the way the hardware inputs do.
+#[derive(Default)]
+pub struct Pad {
+    inputs: FrugInputs,
+}
+
+impl Pad {
+    pub fn tick(&mut self, held: u8) -> &FrugInputs {
+        let inputs = &mut self.inputs;
+        let buttons = [
+            (&mut inputs.a, A),
+            (&mut inputs.b, B),
+            (&mut inputs.left, LEFT),
+            (&mut inputs.right, RIGHT),
+            (&mut inputs.up, UP),
+            (&mut inputs.down, DOWN),
+        ];
+        for (button, bit) in buttons {
+            *button = match (held & bit != 0, button.down()) {
+                (true, false) => ButtonState::PRESSED,
+                (true, true) => ButtonState::DOWN,
+                (false, true) => ButtonState::RELEASED,
+                (false, false) => ButtonState::UP,
+            };
+        }
+        &self.inputs
+    }
+}
+
+/// One frame of `game` with `inputs`, drawn the way the firmware does.
+pub fn step<G>(game: &mut G, inputs: &FrugInputs, screen: &mut Screen)
+where
+    G: FruggerGame<Color = BinaryColor, Engine = OneBit>,
+{
+    game.update(inputs);
+    let engine = game.frugger();
+    for _ in 0..engine.subframes() {
+        engine.draw_frame(screen);
+    }
+}
+
+/// Play `script`, each entry holds its buttons for that many frames. `each` gets the frame
+/// number, counting from 1, after every frame is drawn.
+pub fn play<G>(game: &mut G, script: &[(u8, u32)], mut each: impl FnMut(u32, &Screen))
+where
+    G: FruggerGame<Color = BinaryColor, Engine = OneBit>,
+{
+    let mut screen = Screen::new();
+    let mut pad = Pad::default();
+    let mut frame = 0;
+    for &(held, frames) in script {
+        for _ in 0..frames {
+            step(game, pad.tick(held), &mut screen);
+            frame += 1;
+            each(frame, &screen);
+        }
+    }
+}
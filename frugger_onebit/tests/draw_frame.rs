@@ -0,0 +1,225 @@
+//! `draw_frame` only sends the pixels that changed, so what is on the display depends on the
+//! engine tracking it right. Checked against a display that records every call.
+
+mod common;
+
+use common::*;
+use embedded_graphics::pixelcolor::{BinaryColor, Gray2};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, PrimitiveStyle, Rectangle};
+use frugger_core::mock::Recorder;
+use frugger_core::{FruggerEngine, FruggerGame, Orientation};
+use frugger_onebit::menu::Menu;
+use frugger_onebit::OneBit;
+
+fn display() -> Recorder<BinaryColor> {
+    Recorder::new(Size::new(WIDTH as u32, HEIGHT as u32), BinaryColor::Off)
+}
+
+/// The display shows what the engine thinks it does.
+fn assert_showing(display: &Recorder<BinaryColor>, engine: &OneBit, when: &str) {
+    let expected = engine.snapshot();
+    let wrong = display
+        .pixels()
+        .iter()
+        .enumerate()
+        .filter(|(idx, col)| expected.get(*idx) != **col)
+        .count();
+    assert_eq!(wrong, 0, "{when}: {wrong} pixels are wrong on the display");
+}
+
+fn draw_frame(engine: &mut OneBit, display: &mut Recorder<BinaryColor>) {
+    display.reset_counts();
+    for _ in 0..engine.subframes() {
+        engine.draw_frame(display);
+    }
+}
+
+#[test]
+fn still_frames_push_nothing() {
+    let mut engine = OneBit::new(Orientation::Portrait);
+    let mut display = display();
+    let circle =
+        Circle::new(Point::new(10, 10), 20).into_styled(PrimitiveStyle::with_fill(BinaryColor::On));
+
+    circle.draw(&mut engine).unwrap();
+    draw_frame(&mut engine, &mut display);
+    assert_eq!(
+        display.pushed(),
+        display.pixels().iter().filter(|c| c.is_on()).count()
+    );
+    assert_showing(&display, &engine, "first frame");
+
+    for _ in 0..3 {
+        circle.draw(&mut engine).unwrap();
+        draw_frame(&mut engine, &mut display);
+        assert_eq!(display.pushed(), 0);
+        assert_showing(&display, &engine, "same frame again");
+    }
+
+    circle
+        .translate(Point::new(1, 0))
+        .draw(&mut engine)
+        .unwrap();
+    draw_frame(&mut engine, &mut display);
+    assert_eq!(display.unchanged(), 0);
+    assert!(display.pushed() > 0);
+    assert_showing(&display, &engine, "moved");
+}
+
+#[test]
+fn clear_buffer_redraws_everything() {
+    let mut engine = OneBit::new(Orientation::Portrait);
+    let mut display = display();
+    let left = Rectangle::new(Point::new(0, 0), Size::new(32, 128));
+    let right = Rectangle::new(Point::new(32, 0), Size::new(32, 128));
+
+    engine.fill_solid(&left, BinaryColor::On).unwrap();
+    draw_frame(&mut engine, &mut display);
+
+    // On where the display is Off and the other way round
+    engine.clear_buffer();
+    engine.fill_solid(&right, BinaryColor::On).unwrap();
+    draw_frame(&mut engine, &mut display);
+    assert_eq!(display.pushed(), WIDTH * HEIGHT);
+    assert_showing(&display, &engine, "after clear_buffer");
+    assert_eq!(display.get(Point::new(0, 0)), Some(BinaryColor::Off));
+    assert_eq!(display.get(Point::new(40, 0)), Some(BinaryColor::On));
+}
+
+#[test]
+fn gray_subframes() {
+    let mut engine = OneBit::new(Orientation::Portrait);
+    let mut display = display();
+    engine.set_gray(true);
+    let light = Rectangle::new(Point::new(0, 0), Size::new(8, 8));
+    let dark = Rectangle::new(Point::new(8, 0), Size::new(8, 8));
+
+    for _ in 0..2 {
+        let mut gray = engine.gray().unwrap();
+        gray.fill_solid(&light, Gray2::new(1)).unwrap();
+        gray.fill_solid(&dark, Gray2::new(2)).unwrap();
+
+        let mut lit = Vec::new();
+        for subframe in 0..engine.subframes() {
+            display.reset_counts();
+            engine.draw_frame(&mut display);
+            assert_eq!(display.unchanged(), 0);
+            assert_showing(&display, &engine, &format!("subframe {subframe}"));
+            lit.push((
+                display.get(light.top_left).unwrap().is_on(),
+                display.get(dark.top_left).unwrap().is_on(),
+            ));
+        }
+        assert_eq!(lit, [(false, true), (false, true), (true, false)]);
+    }
+}
+
+#[test]
+fn gray_off_part_way() {
+    let mut engine = OneBit::new(Orientation::Portrait);
+    let mut display = display();
+    let left = Rectangle::new(Point::new(0, 0), Size::new(8, 8));
+    let right = Rectangle::new(Point::new(8, 0), Size::new(8, 8));
+
+    // Inverted so a stale frame would show up everywhere
+    engine.set_gray(true);
+    engine.flash(1);
+    engine.fill_solid(&left, BinaryColor::On).unwrap();
+    engine
+        .gray()
+        .unwrap()
+        .fill_solid(&right, Gray2::new(2))
+        .unwrap();
+    engine.draw_frame(&mut display);
+    assert_eq!(display.get(Point::new(20, 20)), Some(BinaryColor::On));
+
+    engine.set_gray(false);
+    assert_eq!(engine.subframes(), 1);
+    engine.fill_solid(&right, BinaryColor::On).unwrap();
+    draw_frame(&mut engine, &mut display);
+    assert_eq!(display.unchanged(), 0);
+    assert_showing(&display, &engine, "after gray");
+    assert_eq!(display.get(left.top_left), Some(BinaryColor::Off));
+    assert_eq!(display.get(right.top_left), Some(BinaryColor::On));
+    assert_eq!(display.get(Point::new(20, 20)), Some(BinaryColor::Off));
+}
+
+#[test]
+fn background_shakes() {
+    let mut engine = OneBit::new(Orientation::Portrait);
+    let mut display = display();
+    let dot = Rectangle::new(Point::new(20, 20), Size::new(1, 1));
+    engine
+        .background()
+        .fill_solid(&dot, BinaryColor::On)
+        .unwrap();
+    engine.shake(3, 20);
+
+    // Drawn over the background each frame, so they only line up if both move
+    let mut moved = 0;
+    for frame in 0..20 {
+        engine.fill_solid(&dot, BinaryColor::On).unwrap();
+        draw_frame(&mut engine, &mut display);
+        let lit: Vec<_> = display
+            .pixels()
+            .iter()
+            .enumerate()
+            .filter(|(_, col)| **col == BinaryColor::On)
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(lit.len(), 1, "frame {frame}");
+        moved += (lit[0] != 20 * WIDTH + 20) as usize;
+    }
+    assert!(moved > 10);
+}
+
+fn empty_save() -> [u8; 1024] {
+    [0; 1024]
+}
+
+fn ignore_save(_: usize, _: [u8; 32]) {}
+
+#[test]
+fn menu_and_games() {
+    // Transitions between engines, a racer crash with shake and flash and the name entry, all
+    // without a wasted or a missed pixel
+    let script = [
+        (NONE, 30),
+        (DOWN, 1),
+        (NONE, 5),
+        (DOWN, 1),
+        (NONE, 20),
+        (A, 1),
+        (LEFT, 120),
+        (NONE, 60),
+        (A, 1),
+        (NONE, 60),
+        (A, 1),
+        (NONE, 40),
+    ];
+    let mut menu = Menu::new(empty_save, ignore_save);
+    let mut display = display();
+    let mut pad = Pad::default();
+    let mut frame = 0;
+    for &(held, frames) in &script {
+        for _ in 0..frames {
+            frame += 1;
+            menu.update(pad.tick(held));
+            let engine = menu.frugger();
+            draw_frame(engine, &mut display);
+
+            assert_eq!(
+                display.unchanged(),
+                0,
+                "frame {frame} pushed pixels that didn't change"
+            );
+            assert_eq!(
+                display.outside(),
+                0,
+                "frame {frame} pushed pixels off the display"
+            );
+            assert_showing(&display, engine, &format!("frame {frame}"));
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Random buttons for long sessions, looking for panics and games that play differently from the
+//! same seed and buttons. Tests build with overflow checks and debug asserts on, so arithmetic
+//! overflow panics too.
+//!
+//! `FRUGGER_FUZZ_SESSIONS` and `FRUGGER_FUZZ_FRAMES` make the runs longer, a failure prints the
+//! `FRUGGER_FUZZ_SEED` that replays it on its own.
+
+mod common;
+
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+
+use common::*;
+use embedded_graphics::pixelcolor::BinaryColor;
+use frugger_core::FruggerGame;
+use frugger_onebit::games::match_me::MatchMe;
+use frugger_onebit::games::racer::Racer;
+use frugger_onebit::games::triangle_jump::Jump;
+use frugger_onebit::games::worm::SmolWorm;
+use frugger_onebit::menu::Menu;
+use frugger_onebit::OneBit;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+const SEED: u64 = 0xf022;
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Buttons held for a random number of frames at a time, with some quick taps and some frames
+/// with nothing held.
+fn random_script(seed: u64, frames: u32) -> Vec<(u8, u32)> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut script = Vec::new();
+    let mut total = 0;
+    while total < frames {
+        let held = if rng.gen_bool(0.3) {
+            NONE
+        } else {
+            rng.gen_range(1..64)
+        };
+        let hold = if rng.gen_bool(0.5) {
+            rng.gen_range(1..4)
+        } else {
+            rng.gen_range(4..90)
+        };
+        script.push((held, hold));
+        total += hold;
+    }
+    script
+}
+
+/// A hash of every frame, or the frame it panicked on and why.
+fn run<G>(game: &mut G, script: &[(u8, u32)]) -> Result<Vec<u64>, (u32, String)>
+where
+    G: FruggerGame<Color = BinaryColor, Engine = OneBit>,
+{
+    let mut hashes = Vec::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        play(game, script, |_, screen| {
+            let mut hasher = DefaultHasher::new();
+            screen.hash(&mut hasher);
+            hashes.push(hasher.finish());
+        })
+    }));
+
+    match result {
+        Ok(()) => Ok(hashes),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            // The panicking frame never got hashed
+            Err((hashes.len() as u32 + 1, message))
+        }
+    }
+}
+
+/// Play sessions of `name` made by `make` from the session's seed, twice each.
+fn fuzz<G>(name: &str, make: impl Fn(u64) -> G)
+where
+    G: FruggerGame<Color = BinaryColor, Engine = OneBit>,
+{
+    let seeds: Vec<u64> = match env::var("FRUGGER_FUZZ_SEED") {
+        Ok(seed) => vec![seed.parse().expect("FRUGGER_FUZZ_SEED is a number")],
+        Err(_) => {
+            let sessions = env_or("FRUGGER_FUZZ_SESSIONS", 2);
+            (0..sessions).map(|session| SEED + session).collect()
+        }
+    };
+    let frames = env_or("FRUGGER_FUZZ_FRAMES", 1200);
+
+    let mut failures = Vec::new();
+    for seed in seeds {
+        let script = random_script(seed, frames);
+        let replay = format!("{name} with FRUGGER_FUZZ_SEED={seed}");
+
+        let first = match run(&mut make(seed), &script) {
+            Ok(hashes) => hashes,
+            Err((frame, message)) => {
+                failures.push(format!("{replay}: panicked on frame {frame}: {message}"));
+                continue;
+            }
+        };
+        match run(&mut make(seed), &script) {
+            Ok(second) => {
+                if let Some(frame) = first.iter().zip(&second).position(|(a, b)| a != b) {
+                    failures.push(format!(
+                        "{replay}: played differently the second time from frame {}",
+                        frame + 1
+                    ));
+                }
+            }
+            Err((frame, message)) => failures.push(format!(
+                "{replay}: panicked on frame {frame} only the second time: {message}"
+            )),
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+thread_local! {
+    /// Save storage for the menu, per test thread.
+    static STORAGE: RefCell<[u8; 1024]> = const { RefCell::new([0; 1024]) };
+}
+
+fn load() -> [u8; 1024] {
+    STORAGE.with(|storage| *storage.borrow())
+}
+
+fn save(offset: usize, scores: [u8; 32]) {
+    STORAGE.with(|storage| storage.borrow_mut()[offset..offset + 32].copy_from_slice(&scores));
+}
+
+/// Never written, erased, or up to five saved scores for each game.
+fn random_storage(seed: u64) -> [u8; 1024] {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut storage = match seed % 3 {
+        0 => return [0; 1024],
+        1 => return [0xFF; 1024],
+        _ => [0; 1024],
+    };
+    for table in storage[..4 * 32].chunks_mut(32) {
+        let mut score = rng.gen_range(0..2000u16);
+        for record in table[..30].chunks_mut(6).take(rng.gen_range(0..=5)) {
+            record[0] = u8::MAX;
+            // 'A' to 'Z'
+            rng.fill(&mut record[1..4]);
+            record[1..4].iter_mut().for_each(|byte| *byte %= 26);
+            record[4..6].copy_from_slice(&score.to_le_bytes());
+            score = rng.gen_range(0..=score);
+        }
+    }
+    storage
+}
+
+#[test]
+fn jump() {
+    fuzz("jump", Jump::new);
+}
+
+#[test]
+fn worm() {
+    fuzz("worm", SmolWorm::new);
+}
+
+#[test]
+fn racer() {
+    fuzz("racer", Racer::new);
+}
+
+#[test]
+fn match_me() {
+    fuzz("match_me", MatchMe::new);
+}
+
+#[test]
+fn menu() {
+    // Every game, the score screens and saving, from storage made from the seed each time
+    fuzz("menu", |seed| {
+        STORAGE.with(|storage| *storage.borrow_mut() = random_storage(seed));
+        Menu::new(load, save)
+    });
+}
@@ -0,0 +1,228 @@
+//! Golden frames. Each game runs from a fixed seed with scripted buttons, and the frames picked
+//! out are compared with the images in `tests/snapshots/`. After a change that is meant to alter
+//! what is drawn, run with `FRUGGER_BLESS=1` to write new images, then look them over.
+
+mod common;
+
+use std::path::PathBuf;
+use std::{env, fs};
+
+use common::*;
+use embedded_graphics::pixelcolor::BinaryColor;
+use frugger_core::{FrugInputs, FruggerGame, Orientation};
+use frugger_onebit::assets;
+use frugger_onebit::games::match_me::MatchMe;
+use frugger_onebit::games::racer::Racer;
+use frugger_onebit::games::triangle_jump::Jump;
+use frugger_onebit::games::worm::SmolWorm;
+use frugger_onebit::hi_score::HiScore;
+use frugger_onebit::menu::Menu;
+use frugger_onebit::splash::Splash;
+use frugger_onebit::OneBit;
+
+const SEED: u64 = 0x5eed;
+
+/// Checks frames against the stored images, or writes them when blessing.
+struct Golden {
+    bless: bool,
+    failures: Vec<String>,
+    checked: usize,
+}
+
+impl Golden {
+    fn new() -> Self {
+        Self {
+            bless: env::var_os("FRUGGER_BLESS").is_some(),
+            failures: Vec::new(),
+            checked: 0,
+        }
+    }
+
+    fn path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/snapshots")
+            .join(format!("{name}.pbm"))
+    }
+
+    fn check(&mut self, name: &str, screen: &Screen) {
+        self.checked += 1;
+        let path = Self::path(name);
+        if self.bless {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, screen.to_pbm()).unwrap();
+            return;
+        }
+
+        let Ok(pbm) = fs::read_to_string(&path) else {
+            self.failures.push(format!(
+                "{name}: no snapshot, run with FRUGGER_BLESS=1 to add it"
+            ));
+            return;
+        };
+        match Screen::from_pbm(&pbm) {
+            None => self
+                .failures
+                .push(format!("{name}: not a 64x128 plain PBM")),
+            Some(expected) if expected != *screen => self.failures.push(format!(
+                "{name}: differs, expected then actual\n{}",
+                screen.diff(&expected)
+            )),
+            Some(_) => {}
+        }
+    }
+
+    fn finish(self, expected: usize) {
+        assert_eq!(
+            self.checked, expected,
+            "the script ends before the last frame"
+        );
+        assert!(self.failures.is_empty(), "\n{}", self.failures.join("\n"));
+    }
+}
+
+/// Play `script` and check `frames`, named after the game and frame number.
+fn snapshots<G>(name: &str, game: &mut G, script: &[(u8, u32)], frames: &[u32])
+where
+    G: FruggerGame<Color = BinaryColor, Engine = OneBit>,
+{
+    let mut golden = Golden::new();
+    play(game, script, |frame, screen| {
+        if frames.contains(&frame) {
+            golden.check(&format!("{name}_{frame:04}"), screen);
+        }
+    });
+    golden.finish(frames.len());
+}
+
+/// The high score screen on its own engine, it is normally a scene in the menu.
+struct Scores {
+    scores: HiScore,
+    engine: OneBit,
+}
+
+impl FruggerGame for Scores {
+    const TARGET_FPS: u64 = 60;
+    const ORIENTATION: Orientation = Orientation::Portrait;
+    type Color = BinaryColor;
+    type Engine = OneBit;
+
+    fn update(&mut self, inputs: &FrugInputs) {
+        self.scores.update(inputs, &mut self.engine);
+    }
+
+    fn frugger(&mut self) -> &mut Self::Engine {
+        &mut self.engine
+    }
+}
+
+fn empty_save() -> [u8; 1024] {
+    [0; 1024]
+}
+
+fn ignore_save(_: usize, _: [u8; 32]) {}
+
+#[test]
+fn jump() {
+    let script = [(NONE, 30), (RIGHT, 30), (LEFT, 45), (NONE, 75)];
+    snapshots(
+        "jump",
+        &mut Jump::new(SEED),
+        &script,
+        &[1, 30, 60, 105, 180],
+    );
+}
+
+#[test]
+fn worm() {
+    let script = [(NONE, 40), (LEFT, 40), (NONE, 40), (RIGHT, 60)];
+    snapshots("worm", &mut SmolWorm::new(SEED), &script, &[1, 40, 80, 180]);
+}
+
+#[test]
+fn racer() {
+    // Into the left wall, then long enough for the explosion to play out
+    let script = [(NONE, 40), (RIGHT, 10), (LEFT, 40), (NONE, 110)];
+    snapshots(
+        "racer",
+        &mut Racer::new(SEED),
+        &script,
+        &[1, 45, 66, 90, 200],
+    );
+}
+
+#[test]
+fn match_me() {
+    let script = [
+        (NONE, 240),
+        (LEFT, 2),
+        (NONE, 20),
+        (A, 2),
+        (NONE, 20),
+        (RIGHT, 2),
+        (NONE, 60),
+    ];
+    snapshots(
+        "match",
+        &mut MatchMe::new(SEED),
+        &script,
+        &[1, 120, 240, 241, 284, 346],
+    );
+}
+
+#[test]
+fn hi_score() {
+    // Two saved scores, the new one goes between them
+    let mut loaded = [0u8; 32];
+    for (record, (name, score)) in [(b"ABC", 500u16), (b"XYZ", 100)].iter().enumerate() {
+        let offset = record * 6;
+        loaded[offset] = u8::MAX;
+        for (i, byte) in name.iter().enumerate() {
+            loaded[offset + 1 + i] = byte.wrapping_sub(65);
+        }
+        loaded[offset + 4..offset + 6].copy_from_slice(&score.to_le_bytes());
+    }
+
+    let mut scores = Scores {
+        scores: HiScore::new(&loaded, 250, 0, ignore_save),
+        engine: OneBit::new(Orientation::Portrait),
+    };
+    // Spell BBA, which fills the name, then A on the done marker
+    let script = [
+        (NONE, 10),
+        (RIGHT, 1),
+        (NONE, 1),
+        (A, 1),
+        (NONE, 1),
+        (A, 1),
+        (NONE, 10),
+        (LEFT, 1),
+        (NONE, 1),
+        (A, 1),
+        (NONE, 10),
+        (A, 1),
+        (NONE, 10),
+    ];
+    snapshots("hi_score", &mut scores, &script, &[1, 13, 28, 39, 49]);
+}
+
+#[test]
+fn menu() {
+    let mut menu = Menu::new(empty_save, ignore_save)
+        .splash(Splash::new(assets::LOGO, "0.1.0").status("STORAGE", true));
+    // Splash, skip it, down to Worm and start it
+    let script = [
+        (NONE, 60),
+        (A, 1),
+        (NONE, 40),
+        (DOWN, 1),
+        (NONE, 20),
+        (A, 1),
+        (NONE, 40),
+    ];
+    snapshots(
+        "menu",
+        &mut menu,
+        &script,
+        &[10, 60, 73, 101, 122, 134, 163],
+    );
+}
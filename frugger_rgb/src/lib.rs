@@ -1,3 +1,5 @@
+#![cfg_attr(not(test), no_std)]
+
 use core::convert::Infallible;
 
 use embedded_graphics::draw_target::DrawTarget;
@@ -7,13 +9,15 @@ use embedded_graphics::prelude::RgbColor;
 use embedded_graphics::primitives::Rectangle;
 use embedded_graphics::Pixel;
 
-use crate::{FruggerEngine, Palette};
+use frugger_core::{FruggerEngine, Palette};
 
 pub struct Frugger {
     // 320 * 240 / 2
     default_val: u8,
     last_frame: [u8; 38400],
     next_frame: [u8; 38400],
+    /// Send every pixel next frame, `last_frame` isn't what is on the display.
+    redraw: bool,
 }
 
 impl Dimensions for Frugger {
@@ -48,13 +52,13 @@ impl FruggerEngine<Rgb565> for Frugger {
                 let next = self.get_pixel_value_next(x, y);
                 let last = self.get_pixel_value(x, y);
 
-                if next != last {
+                if self.redraw || next != last {
                     if run_start == -1 { run_start = x as _; }
                     cols[run_length] = next.into();
                     run_length += 1;
-                } else if run_start != -1 && (next == last) {
+                } else if run_start != -1 {
                     let area = Rectangle::new(Point::new(run_start, y as _), Size::new(run_length as _, 1));
-                    display.fill_contiguous(&area, cols);
+                    let _ = display.fill_contiguous(&area, cols);
 
                     run_length = 0;
                     run_start = -1;
@@ -62,13 +66,14 @@ impl FruggerEngine<Rgb565> for Frugger {
 
                 if x == 319 && run_start != -1 {
                     let area = Rectangle::new(Point::new(run_start, y as _), Size::new(run_length as _, 1));
-                    display.fill_contiguous(&area, cols);
+                    let _ = display.fill_contiguous(&area, cols);
                 }
             }
         }
 
         self.last_frame.copy_from_slice(&self.next_frame);
         self.next_frame.fill(self.default_val);
+        self.redraw = false;
     }
 }
 
@@ -77,8 +82,9 @@ impl Frugger {
         let default_val = bg_col.bits() | (bg_col.bits() << 4);
         Self {
             default_val,
-            last_frame: [u8::MAX; 38400],
+            last_frame: [default_val; 38400],
             next_frame: [default_val; 38400],
+            redraw: true,
         }
     }
     fn get_pixel_value(&self, x: u16, y: u16) -> Palette {
@@ -0,0 +1,84 @@
+//! `draw_frame` sends runs of the pixels that changed on each row, checked against a display that
+//! records every call.
+
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use frugger_core::mock::{Call, Recorder};
+use frugger_core::{FruggerEngine, Palette};
+use frugger_rgb::Frugger;
+
+const SIZE: Size = Size::new(320, 240);
+
+fn display() -> Recorder<Rgb565> {
+    Recorder::new(SIZE, Rgb565::BLACK)
+}
+
+/// Every pixel in `area` is `col`, and every other one `bg`.
+fn assert_showing(display: &Recorder<Rgb565>, area: Rectangle, col: Palette, bg: Palette) {
+    let wrong = Rectangle::new(Point::zero(), SIZE)
+        .points()
+        .filter(|p| {
+            let expected = if area.contains(*p) { col } else { bg };
+            display.get(*p) != Some(expected.into())
+        })
+        .count();
+    assert_eq!(wrong, 0, "{wrong} pixels are wrong on the display");
+}
+
+#[test]
+fn first_frame_sends_everything() {
+    // The background used to be the "never drawn" value, so it was never sent
+    let mut engine = Frugger::new(Palette::BlueGrey);
+    let mut display = display();
+
+    engine.draw_frame(&mut display);
+    assert_eq!(display.pushed(), 320 * 240);
+    assert_eq!(display.calls().len(), 240);
+    assert_showing(&display, Rectangle::zero(), Palette::Red, Palette::BlueGrey);
+}
+
+#[test]
+fn runs_of_changes() {
+    let mut engine = Frugger::new(Palette::Black);
+    let mut display = display();
+    engine.draw_frame(&mut display);
+
+    // One against the right edge, so the run ends with the row
+    for area in [
+        Rectangle::new(Point::new(10, 5), Size::new(20, 3)),
+        Rectangle::new(Point::new(300, 100), Size::new(20, 2)),
+    ] {
+        engine.fill_solid(&area, Palette::Red).unwrap();
+        display.reset_counts();
+        engine.draw_frame(&mut display);
+        let rows: Vec<_> = area
+            .rows()
+            .map(|y| {
+                Call::Contiguous(Rectangle::new(
+                    Point::new(area.top_left.x, y),
+                    Size::new(area.size.width, 1),
+                ))
+            })
+            .collect();
+        assert_eq!(display.calls(), rows);
+        assert_eq!(display.unchanged(), 0);
+        assert_showing(&display, area, Palette::Red, Palette::Black);
+
+        // Same again sends nothing
+        engine.fill_solid(&area, Palette::Red).unwrap();
+        display.reset_counts();
+        engine.draw_frame(&mut display);
+        assert_eq!(display.pushed(), 0);
+
+        // Not drawn goes back to the background
+        display.reset_counts();
+        engine.draw_frame(&mut display);
+        assert_eq!(
+            display.pushed(),
+            area.size.width as usize * area.size.height as usize
+        );
+        assert_eq!(display.unchanged(), 0);
+        assert_showing(&display, Rectangle::zero(), Palette::Red, Palette::Black);
+    }
+}
@@ -0,0 +1,473 @@
+//! Runs OneBit games in a terminal, for debugging on a host without a GUI (or over SSH).
+//! `Terminal` is the display and `Keyboard` the buttons, see `main.rs` for the game loop.
+//!
+//! The root `.cargo/config.toml` builds for the device, so give the host target:
+//! `cargo run --target x86_64-unknown-linux-gnu`, add `-- --half` for half block cells.
+
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::style::Print;
+use crossterm::{cursor, queue, terminal};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use frugger_core::{ButtonInput, ButtonState, FrugInputs};
+
+/// How pixels map to character cells.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Cells {
+    /// 2x4 pixels a cell, about square in most fonts.
+    Braille,
+    /// 1x2 pixels a cell, bigger but twice as tall as it is wide.
+    HalfBlock,
+}
+
+impl Cells {
+    fn size(self) -> Size {
+        match self {
+            Cells::Braille => Size::new(2, 4),
+            Cells::HalfBlock => Size::new(1, 2),
+        }
+    }
+
+    /// Character for the cell with its top left pixel at `x`, `y`.
+    fn char_at(self, on: impl Fn(u32, u32) -> bool, x: u32, y: u32) -> char {
+        match self {
+            Cells::Braille => {
+                // Braille dot bits, the bottom row was added to the set later so its bits come last
+                const DOTS: [(u32, u32, u32); 8] = [
+                    (0, 0, 0x01),
+                    (0, 1, 0x02),
+                    (0, 2, 0x04),
+                    (1, 0, 0x08),
+                    (1, 1, 0x10),
+                    (1, 2, 0x20),
+                    (0, 3, 0x40),
+                    (1, 3, 0x80),
+                ];
+                let bits = DOTS
+                    .iter()
+                    .filter(|(dx, dy, _)| on(x + dx, y + dy))
+                    .fold(0, |bits, (_, _, bit)| bits | bit);
+                char::from_u32(0x2800 + bits).unwrap_or(' ')
+            }
+            Cells::HalfBlock => match (on(x, y), on(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            },
+        }
+    }
+}
+
+/// Display drawn with text. The terminal is in raw mode on the alternate screen until this is
+/// dropped. Pixels are buffered, `flush` writes the cells that changed.
+pub struct Terminal {
+    out: Stdout,
+    size: Size,
+    cells: Cells,
+    pixels: Vec<bool>,
+    /// What each cell shows now, `None` before the first flush.
+    shown: Vec<Option<char>>,
+}
+
+impl Terminal {
+    pub fn new(size: Size, cells: Cells) -> io::Result<Self> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        let entered = queue!(
+            out,
+            terminal::EnterAlternateScreen,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::Hide
+        );
+        if let Err(err) = entered {
+            Self::restore(&mut out);
+            return Err(err);
+        }
+
+        let Size { width, height } = Self::grid(size, cells);
+        Ok(Self {
+            out,
+            size,
+            cells,
+            pixels: vec![false; (size.width * size.height) as usize],
+            shown: vec![None; (width * height) as usize],
+        })
+    }
+
+    /// Undo `new`, as far as it got.
+    fn restore(out: &mut Stdout) {
+        let _ = queue!(out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+
+    /// Size in cells.
+    fn grid(size: Size, cells: Cells) -> Size {
+        let cell = cells.size();
+        Size::new(
+            size.width.div_ceil(cell.width),
+            size.height.div_ceil(cell.height),
+        )
+    }
+
+    fn on(&self, x: u32, y: u32) -> bool {
+        x < self.size.width
+            && y < self.size.height
+            && self.pixels[(y * self.size.width + x) as usize]
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        let grid = Self::grid(self.size, self.cells);
+        let cell = self.cells.size();
+
+        if self.shown.iter().all(Option::is_none) {
+            self.draw_border(grid)?;
+        }
+
+        for row in 0..grid.height {
+            for col in 0..grid.width {
+                let c =
+                    self.cells
+                        .char_at(|x, y| self.on(x, y), col * cell.width, row * cell.height);
+                let shown = &mut self.shown[(row * grid.width + col) as usize];
+                if *shown != Some(c) {
+                    *shown = Some(c);
+                    // Inside the border
+                    queue!(
+                        self.out,
+                        cursor::MoveTo(col as u16 + 1, row as u16 + 1),
+                        Print(c)
+                    )?;
+                }
+            }
+        }
+        self.out.flush()
+    }
+
+    fn draw_border(&mut self, grid: Size) -> io::Result<()> {
+        let line = "─".repeat(grid.width as usize);
+        queue!(self.out, cursor::MoveTo(0, 0), Print(format!("┌{line}┐")))?;
+        for row in 1..=grid.height as u16 {
+            queue!(
+                self.out,
+                cursor::MoveTo(0, row),
+                Print('│'),
+                cursor::MoveTo(grid.width as u16 + 1, row),
+                Print('│')
+            )?;
+        }
+        queue!(
+            self.out,
+            cursor::MoveTo(0, grid.height as u16 + 1),
+            Print(format!("└{line}┘")),
+            cursor::MoveTo(0, grid.height as u16 + 2),
+            Print(Keyboard::HELP)
+        )
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        Self::restore(&mut self.out);
+    }
+}
+
+impl Dimensions for Terminal {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), self.size)
+    }
+}
+
+impl DrawTarget for Terminal {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let Size { width, height } = self.size;
+        for Pixel(p, col) in pixels {
+            if p.x >= 0 && p.y >= 0 && (p.x as u32) < width && (p.y as u32) < height {
+                self.pixels[(p.y as u32 * width + p.x as u32) as usize] = col.is_on();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Key {
+    down: bool,
+    /// Went down since the last tick, so taps shorter than a frame still count.
+    hit: bool,
+    seen: Instant,
+}
+
+/// Buttons from the keyboard: arrows or WASD, A is Z, J or space, B is X, K or backspace. Esc or
+/// Ctrl+C quits. Terminals that can't report releases get a key held while it keeps repeating,
+/// so holding one reads as a tap, then held once the keyboard starts repeating.
+pub struct Keyboard {
+    /// a, b, left, right, up, down.
+    keys: [Key; 6],
+    releases: bool,
+    quit: bool,
+}
+
+impl Keyboard {
+    pub const HELP: &'static str = "arrows/WASD  A: Z J space  B: X K backspace  quit: Esc";
+    /// Without release events, how long a key stays down after its last press or repeat.
+    const HOLD: Duration = Duration::from_millis(120);
+
+    /// Call after `Terminal::new`, asking the terminal what it supports needs raw mode.
+    pub fn new() -> io::Result<Self> {
+        let releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if releases {
+            let mut out = io::stdout();
+            queue!(
+                out,
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+            out.flush()?;
+        }
+        Ok(Self::with_releases(releases))
+    }
+
+    /// `releases` is whether the terminal reports key releases.
+    fn with_releases(releases: bool) -> Self {
+        let key = Key {
+            down: false,
+            hit: false,
+            seen: Instant::now(),
+        };
+        Self {
+            keys: [key; 6],
+            releases,
+            quit: false,
+        }
+    }
+
+    /// Esc or Ctrl+C was pressed.
+    pub fn quit(&self) -> bool {
+        self.quit
+    }
+
+    fn button(code: KeyCode) -> Option<usize> {
+        match code {
+            KeyCode::Char('z' | 'j' | ' ') | KeyCode::Enter => Some(0),
+            KeyCode::Char('x' | 'k') | KeyCode::Backspace => Some(1),
+            KeyCode::Left | KeyCode::Char('a') => Some(2),
+            KeyCode::Right | KeyCode::Char('d') => Some(3),
+            KeyCode::Up | KeyCode::Char('w') => Some(4),
+            KeyCode::Down | KeyCode::Char('s') => Some(5),
+            _ => None,
+        }
+    }
+
+    fn handle(&mut self, event: KeyEvent, now: Instant) {
+        let ctrl_c =
+            event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL);
+        if event.code == KeyCode::Esc || ctrl_c {
+            self.quit = true;
+            return;
+        }
+
+        let code = match event.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        let Some(idx) = Self::button(code) else {
+            return;
+        };
+        let key = &mut self.keys[idx];
+        match event.kind {
+            KeyEventKind::Press => {
+                key.down = true;
+                key.hit = true;
+                key.seen = now;
+            }
+            KeyEventKind::Repeat => {
+                key.down = true;
+                key.seen = now;
+            }
+            KeyEventKind::Release => key.down = false,
+        }
+    }
+}
+
+impl Drop for Keyboard {
+    fn drop(&mut self) {
+        if self.releases {
+            let mut out = io::stdout();
+            let _ = queue!(out, PopKeyboardEnhancementFlags);
+            let _ = out.flush();
+        }
+    }
+}
+
+impl Keyboard {
+    /// Move the buttons on a frame from the keys handled so far.
+    fn update(&mut self, inputs: &mut FrugInputs, now: Instant) {
+        let buttons = [
+            &mut inputs.a,
+            &mut inputs.b,
+            &mut inputs.left,
+            &mut inputs.right,
+            &mut inputs.up,
+            &mut inputs.down,
+        ];
+        for (key, button) in self.keys.iter_mut().zip(buttons) {
+            let held = key.down && (self.releases || now - key.seen < Self::HOLD);
+            let active = held || key.hit;
+            key.hit = false;
+
+            *button = match (active, &*button) {
+                (true, ButtonState::UP | ButtonState::RELEASED) => ButtonState::PRESSED,
+                (true, _) => ButtonState::DOWN,
+                (false, ButtonState::DOWN | ButtonState::PRESSED) => ButtonState::RELEASED,
+                (false, _) => ButtonState::UP,
+            };
+        }
+    }
+}
+
+impl ButtonInput for Keyboard {
+    fn tick(&mut self, inputs: &mut FrugInputs) {
+        while event::poll(Duration::ZERO).unwrap_or(false) {
+            if let Ok(Event::Key(event)) = event::read() {
+                self.handle(event, Instant::now());
+            }
+        }
+        self.update(inputs, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The cell at 2, 4 with only the pixels in `lit` on, relative to it.
+    fn cell(cells: Cells, lit: &[(u32, u32)]) -> char {
+        cells.char_at(|x, y| lit.contains(&(x - 2, y - 4)), 2, 4)
+    }
+
+    #[test]
+    fn braille_dots() {
+        // Down the left column, down the right, then the bottom row
+        let order = [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (0, 3),
+            (1, 3),
+        ];
+        for (bit, dot) in order.iter().enumerate() {
+            let c = cell(Cells::Braille, &[*dot]);
+            assert_eq!(c as u32, 0x2800 + (1 << bit), "{dot:?}");
+        }
+        assert_eq!(cell(Cells::Braille, &[]), '\u{2800}');
+        assert_eq!(cell(Cells::Braille, &order), '⣿');
+        assert_eq!(cell(Cells::Braille, &[(0, 0), (1, 3)]), '⢁');
+    }
+
+    #[test]
+    fn half_blocks() {
+        assert_eq!(cell(Cells::HalfBlock, &[]), ' ');
+        assert_eq!(cell(Cells::HalfBlock, &[(0, 0)]), '▀');
+        assert_eq!(cell(Cells::HalfBlock, &[(0, 1)]), '▄');
+        assert_eq!(cell(Cells::HalfBlock, &[(0, 0), (0, 1)]), '█');
+        // Only its own column
+        assert_eq!(cell(Cells::HalfBlock, &[(1, 0)]), ' ');
+    }
+
+    fn key(code: KeyCode, kind: KeyEventKind) -> KeyEvent {
+        KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind)
+    }
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn with_releases() {
+        let start = Instant::now();
+        let mut keyboard = Keyboard::with_releases(true);
+        let mut inputs = FrugInputs::default();
+
+        keyboard.handle(key(KeyCode::Char('Z'), KeyEventKind::Press), start);
+        keyboard.update(&mut inputs, start);
+        assert!(inputs.a.pressed());
+        // Held well past `HOLD` as the release is reported
+        keyboard.update(&mut inputs, ms(start, 1000));
+        assert!(inputs.a == ButtonState::DOWN);
+
+        keyboard.handle(key(KeyCode::Char('z'), KeyEventKind::Release), start);
+        keyboard.update(&mut inputs, ms(start, 1500));
+        assert!(inputs.a.released());
+        keyboard.update(&mut inputs, ms(start, 1516));
+        assert!(inputs.a == ButtonState::UP);
+
+        // Tapped between frames still counts
+        keyboard.handle(key(KeyCode::Left, KeyEventKind::Press), start);
+        keyboard.handle(key(KeyCode::Left, KeyEventKind::Release), start);
+        keyboard.update(&mut inputs, ms(start, 1532));
+        assert!(inputs.left.pressed());
+        keyboard.update(&mut inputs, ms(start, 1548));
+        assert!(inputs.left.released());
+    }
+
+    #[test]
+    fn without_releases() {
+        let start = Instant::now();
+        let mut keyboard = Keyboard::with_releases(false);
+        let mut inputs = FrugInputs::default();
+
+        keyboard.handle(key(KeyCode::Up, KeyEventKind::Press), start);
+        keyboard.update(&mut inputs, start);
+        assert!(inputs.up.pressed());
+        keyboard.update(&mut inputs, ms(start, 100));
+        assert!(inputs.up == ButtonState::DOWN);
+
+        // Repeats keep it held, it lets go `HOLD` after the last
+        keyboard.handle(key(KeyCode::Up, KeyEventKind::Repeat), ms(start, 110));
+        keyboard.update(&mut inputs, ms(start, 200));
+        assert!(inputs.up == ButtonState::DOWN);
+        keyboard.update(&mut inputs, ms(start, 230));
+        assert!(inputs.up.released());
+        keyboard.update(&mut inputs, ms(start, 250));
+        assert!(inputs.up == ButtonState::UP);
+        assert!(inputs == FrugInputs::default());
+    }
+
+    #[test]
+    fn keys() {
+        let mut keyboard = Keyboard::with_releases(true);
+        let mut inputs = FrugInputs::default();
+        let now = Instant::now();
+        for code in [KeyCode::Char('K'), KeyCode::Char('d'), KeyCode::Char('q')] {
+            keyboard.handle(key(code, KeyEventKind::Press), now);
+        }
+        keyboard.update(&mut inputs, now);
+        assert!(inputs.b.pressed() && inputs.right.pressed());
+        assert!(!inputs.a.down() && !inputs.left.down());
+        assert!(!keyboard.quit());
+
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        keyboard.handle(ctrl_c, now);
+        assert!(keyboard.quit());
+        let mut keyboard = Keyboard::with_releases(true);
+        keyboard.handle(key(KeyCode::Esc, KeyEventKind::Press), now);
+        assert!(keyboard.quit());
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+use std::{env, fs, io, thread};
+
+use embedded_graphics::geometry::Size;
+use frugger_core::{ButtonInput, FrugInputs, FruggerEngine, FruggerGame};
+use frugger_onebit::assets;
+use frugger_onebit::menu::Menu;
+use frugger_onebit::splash::Splash;
+use frugger_term::{Cells, Keyboard, Terminal};
+
+/// High scores, in the working directory.
+const SAVE_FILE: &str = "frugger_save.bin";
+
+fn load() -> [u8; 1024] {
+    let mut data = [0u8; 1024];
+    if let Ok(bytes) = fs::read(SAVE_FILE) {
+        let len = bytes.len().min(data.len());
+        data[..len].copy_from_slice(&bytes[..len]);
+    }
+    data
+}
+
+fn save(offset: usize, scores: [u8; 32]) {
+    let mut data = load();
+    data[offset..offset + 32].copy_from_slice(&scores);
+    let _ = fs::write(SAVE_FILE, data);
+}
+
+fn main() -> io::Result<()> {
+    let cells = if env::args().any(|arg| arg == "--half") {
+        Cells::HalfBlock
+    } else {
+        Cells::Braille
+    };
+
+    let menu = Menu::new(load, save);
+    let storage_ok = menu.storage_ok();
+    let mut menu = menu
+        .splash(Splash::new(assets::LOGO, env!("CARGO_PKG_VERSION")).status("STORAGE", storage_ok));
+
+    // Dropped in reverse, the keyboard has to let go of the terminal first
+    let mut display = Terminal::new(Size::new(64, 128), cells)?;
+    let mut keyboard = Keyboard::new()?;
+    let mut inputs = FrugInputs::default();
+    let frame = Duration::from_micros(1_000_000 / Menu::TARGET_FPS);
+
+    loop {
+        let frame_start = Instant::now();
+
+        keyboard.tick(&mut inputs);
+        if keyboard.quit() {
+            return Ok(());
+        }
+        menu.update(&inputs);
+
+        // Gray mode flickers between subframes, the terminal only shows the last one
+        let engine = menu.frugger();
+        for _ in 0..engine.subframes() {
+            engine.draw_frame(&mut display);
+        }
+        display.flush()?;
+
+        if let Some(rest) = frame.checked_sub(frame_start.elapsed()) {
+            thread::sleep(rest);
+        }
+    }
+}
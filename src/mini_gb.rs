@@ -6,10 +6,11 @@ use sh1106::Builder;
 use bsp::hal::clocks::SystemClock;
 use bsp::hal::{Sio, Timer};
 use bsp::pac;
-use embedded_hal::delay::DelayNs;
-use frugger_core::util::RollingAverage;
+use frugger_core::util::FrameClock;
 use frugger_core::{ButtonInput, FrugInputs, FrugTimer, FruggerEngine, FruggerGame};
+use frugger_onebit::assets;
 use frugger_onebit::menu::Menu;
+use frugger_onebit::splash::Splash;
 use sh1106::interface::DisplayInterface;
 use ssd1306::prelude::DisplayConfig;
 use waveshare_rp2040_zero as bsp;
@@ -29,7 +30,7 @@ impl FrugTimer for HalTimer {
     }
 }
 
-pub(crate) fn start(system_clock: &SystemClock, mut timer: Timer) -> ! {
+pub(crate) fn start(system_clock: &SystemClock, timer: Timer) -> ! {
     // I don't know if I like this, but it seems necessary(?)
     let mut pac = unsafe { pac::Peripherals::steal() };
 
@@ -83,14 +84,16 @@ pub(crate) fn start(system_clock: &SystemClock, mut timer: Timer) -> ! {
     display.set_rotation(DisplayRotation::Rotate90);
 
     // let mut menu=  Menu::new(|| unsafe { DATA_STORAGE }, |offset, data| unsafe { DATA_STORAGE }.copy_from_slice(&data));
-    let mut menu = Menu::new(|| unsafe { DATA_STORAGE }, |offset, data| {});
+    let menu = Menu::new(|| unsafe { DATA_STORAGE }, |offset, data| {});
+    let storage_ok = menu.storage_ok();
+    let mut menu = menu.splash(
+        Splash::new(assets::LOGO, env!("CARGO_PKG_VERSION")).status("STORAGE", storage_ok),
+    );
 
-    let mut logic_avg = RollingAverage::new();
-    let target_fps = 60;
+    let mut timer = HalTimer(timer);
+    let mut clock = FrameClock::new(Menu::TARGET_FPS, &timer);
 
     loop {
-        let frame_start = timer.get_counter().ticks();
-
         // TODO detect x frames held for restart/pause
 
         // Update inputs
@@ -98,20 +101,14 @@ pub(crate) fn start(system_clock: &SystemClock, mut timer: Timer) -> ! {
 
         menu.update(&mut inputs);
 
-        let logic_end = timer.get_counter().ticks();
-        let logic_time = logic_end - frame_start;
-        logic_avg.add(logic_time);
-
-        menu.frugger().draw_frame(&mut display);
-        let _ = display.flush();
-
-        let draw_end = timer.get_counter().ticks();
-        let draw_time = draw_end - logic_end;
-        let total_time = draw_end - frame_start;
+        // Gray mode needs several flushes per frame
+        let engine = menu.frugger();
+        for _ in 0..engine.subframes() {
+            engine.draw_frame(&mut display);
+            let _ = display.flush();
+        }
 
         // TODO render fps?
-        if total_time < target_fps {
-            timer.delay_ms((target_fps - total_time) as u32);
-        }
+        clock.end_frame(&mut timer);
     }
 }